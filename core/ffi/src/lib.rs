@@ -2,9 +2,11 @@
 
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
+use std::sync::OnceLock;
 
-use workflow::{WorkflowEngine, Task, TaskStatus, Stage, GateStatus};
-use knowledge::{KnowledgeManager, Handoff, BudgetStatus};
+use workflow::{ApprovalResponse, AutoAdvance, WorkflowEngine, WorkflowError, Task, TaskStatus, Stage, GateStatus};
+use knowledge::{KnowledgeManager, Handoff, BudgetStatus, Checkpoint, SuccessorContext, TokenCounter};
+use knowledge::checkpoint::CheckpointCompiler;
 use runtime::{HealthMonitor, HealthStatus};
 
 // ============================================================================
@@ -41,6 +43,44 @@ fn from_c_string(ptr: *const c_char) -> Option<String> {
     }
 }
 
+/// Error JSON `{"error_code": ..., "error": ...}`: a stable code callers can
+/// branch on plus a message. Every FFI error is built here.
+fn error_json(code: &str, message: impl std::fmt::Display) -> String {
+    serde_json::json!({
+        "error_code": code,
+        "error": message.to_string(),
+    })
+    .to_string()
+}
+
+/// Error JSON for a `WorkflowError`
+fn workflow_error_json(e: &WorkflowError) -> String {
+    error_json(e.code(), e)
+}
+
+/// Success JSON for an approval: `transition` when it advanced the stage,
+/// `advance_error` when auto-advance was attempted and refused
+fn approval_json(advance: AutoAdvance) -> String {
+    let mut json = serde_json::json!({ "success": true });
+    match advance {
+        AutoAdvance::NotAttempted => {}
+        AutoAdvance::Advanced(t) => {
+            json["transition"] = serde_json::json!({
+                "from": t.from.as_str(),
+                "to": t.to.as_str(),
+                "snapshot_recommended": t.snapshot_recommended,
+            });
+        }
+        AutoAdvance::Blocked(e) => {
+            json["advance_error"] = serde_json::json!({
+                "error_code": e.code(),
+                "error": e.to_string(),
+            });
+        }
+    }
+    json.to_string()
+}
+
 // ============================================================================
 // Workflow Engine FFI
 // ============================================================================
@@ -84,23 +124,23 @@ pub extern "C" fn workflow_engine_create_task(
     task_json: *const c_char,
 ) -> *mut c_char {
     if ptr.is_null() {
-        return to_c_string(r#"{"error": "null engine pointer"}"#);
+        return to_c_string(&error_json("null_pointer", "null engine pointer"));
     }
 
     let json_str = match from_c_string(task_json) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid task JSON"}"#),
+        None => return to_c_string(&error_json("invalid_argument", "invalid task JSON")),
     };
 
     let task: Task = match serde_json::from_str(&json_str) {
         Ok(t) => t,
-        Err(e) => return to_c_string(&format!(r#"{{"error": "{}"}}"#, e)),
+        Err(e) => return to_c_string(&error_json("parse_error", e)),
     };
 
     let engine = unsafe { &mut *ptr };
     let id = engine.create_task(task);
 
-    to_c_string(&format!(r#"{{"task_id": "{}"}}"#, id))
+    to_c_string(&serde_json::json!({ "task_id": id }).to_string())
 }
 
 /// Get ready tasks as JSON array
@@ -119,6 +159,38 @@ pub extern "C" fn workflow_engine_get_ready_tasks(ptr: *const WorkflowEngine) ->
     }
 }
 
+/// Get tasks in the current stage as JSON array
+#[no_mangle]
+pub extern "C" fn workflow_engine_current_stage_tasks(ptr: *const WorkflowEngine) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string("[]");
+    }
+
+    let engine = unsafe { &*ptr };
+    let tasks = engine.current_stage_tasks();
+
+    match serde_json::to_string(&tasks) {
+        Ok(json) => to_c_string(&json),
+        Err(_) => to_c_string("[]"),
+    }
+}
+
+/// Get ready tasks in the current stage as JSON array
+#[no_mangle]
+pub extern "C" fn workflow_engine_current_stage_ready_tasks(ptr: *const WorkflowEngine) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string("[]");
+    }
+
+    let engine = unsafe { &*ptr };
+    let tasks = engine.current_stage_ready_tasks();
+
+    match serde_json::to_string(&tasks) {
+        Ok(json) => to_c_string(&json),
+        Err(_) => to_c_string("[]"),
+    }
+}
+
 /// Get all tasks as JSON array
 #[no_mangle]
 pub extern "C" fn workflow_engine_get_all_tasks(ptr: *const WorkflowEngine) -> *mut c_char {
@@ -143,28 +215,28 @@ pub extern "C" fn workflow_engine_update_task_status(
     status_json: *const c_char,
 ) -> *mut c_char {
     if ptr.is_null() {
-        return to_c_string(r#"{"error": "null engine pointer"}"#);
+        return to_c_string(&error_json("null_pointer", "null engine pointer"));
     }
 
     let id = match from_c_string(task_id) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid task ID"}"#),
+        None => return to_c_string(&error_json("invalid_argument", "invalid task ID")),
     };
 
     let status_str = match from_c_string(status_json) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid status JSON"}"#),
+        None => return to_c_string(&error_json("invalid_argument", "invalid status JSON")),
     };
 
     let status: TaskStatus = match serde_json::from_str(&status_str) {
         Ok(s) => s,
-        Err(e) => return to_c_string(&format!(r#"{{"error": "{}"}}"#, e)),
+        Err(e) => return to_c_string(&error_json("parse_error", e)),
     };
 
     let engine = unsafe { &mut *ptr };
     match engine.update_task_status(&id, status) {
         Ok(()) => to_c_string(r#"{"success": true}"#),
-        Err(e) => to_c_string(&format!(r#"{{"error": "{}"}}"#, e)),
+        Err(e) => to_c_string(&workflow_error_json(&e)),
     }
 }
 
@@ -175,17 +247,17 @@ pub extern "C" fn workflow_engine_check_gate(
     stage_str: *const c_char,
 ) -> *mut c_char {
     if ptr.is_null() {
-        return to_c_string(r#"{"error": "null engine pointer"}"#);
+        return to_c_string(&error_json("null_pointer", "null engine pointer"));
     }
 
     let stage_name = match from_c_string(stage_str) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid stage"}"#),
+        None => return to_c_string(&error_json("invalid_stage", "invalid stage")),
     };
 
-    let stage: Stage = match serde_json::from_str(&format!(r#""{}""#, stage_name)) {
-        Ok(p) => p,
-        Err(_) => return to_c_string(r#"{"error": "unknown stage"}"#),
+    let stage: Stage = match stage_name.parse() {
+        Ok(stage) => stage,
+        Err(e) => return to_c_string(&error_json("invalid_stage", e)),
     };
 
     let engine = unsafe { &*ptr };
@@ -200,7 +272,67 @@ pub extern "C" fn workflow_engine_check_gate(
     to_c_string(&format!(r#"{{"status": "{}"}}"#, status_str))
 }
 
-/// Approve a gate
+/// Gate status for every stage in pipeline order, as one snapshot.
+/// Returns `[{"stage": ..., "status": ...}, ...]`.
+#[no_mangle]
+pub extern "C" fn workflow_engine_all_gate_statuses(ptr: *const WorkflowEngine) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string(&error_json("null_pointer", "null engine pointer"));
+    }
+
+    let engine = unsafe { &*ptr };
+    let statuses: Vec<serde_json::Value> = engine.all_gate_statuses()
+        .into_iter()
+        .map(|(stage, status)| serde_json::json!({ "stage": stage, "status": status }))
+        .collect();
+
+    to_c_string(&serde_json::Value::Array(statuses).to_string())
+}
+
+/// Check whether a stage's gate can be approved, with blocking reasons
+#[no_mangle]
+pub extern "C" fn workflow_engine_can_approve_gate(
+    ptr: *const WorkflowEngine,
+    stage_str: *const c_char,
+) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string(&error_json("null_pointer", "null engine pointer"));
+    }
+
+    let stage_name = match from_c_string(stage_str) {
+        Some(s) => s,
+        None => return to_c_string(&error_json("invalid_stage", "invalid stage")),
+    };
+
+    let stage: Stage = match stage_name.parse() {
+        Ok(stage) => stage,
+        Err(e) => return to_c_string(&error_json("invalid_stage", e)),
+    };
+
+    let engine = unsafe { &*ptr };
+    let (can_approve, reasons) = engine.can_approve_gate(stage);
+    let json = serde_json::json!({
+        "can_approve": can_approve,
+        "reasons": reasons,
+    });
+
+    to_c_string(&json.to_string())
+}
+
+/// Advance to the next stage automatically when the current stage's gate
+/// is approved with its criteria met
+#[no_mangle]
+pub extern "C" fn workflow_engine_set_auto_advance(ptr: *mut WorkflowEngine, enabled: bool) {
+    if ptr.is_null() {
+        return;
+    }
+
+    let engine = unsafe { &mut *ptr };
+    engine.set_auto_advance(enabled);
+}
+
+/// Approve a gate. Includes `transition` when the approval advanced the
+/// current stage, or `advance_error` when auto-advance was refused.
 #[no_mangle]
 pub extern "C" fn workflow_engine_approve_gate(
     ptr: *mut WorkflowEngine,
@@ -208,28 +340,85 @@ pub extern "C" fn workflow_engine_approve_gate(
     approved_by: *const c_char,
 ) -> *mut c_char {
     if ptr.is_null() {
-        return to_c_string(r#"{"error": "null engine pointer"}"#);
+        return to_c_string(&error_json("null_pointer", "null engine pointer"));
     }
 
     let stage_name = match from_c_string(stage_str) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid stage"}"#),
+        None => return to_c_string(&error_json("invalid_stage", "invalid stage")),
     };
 
     let by = match from_c_string(approved_by) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid approver"}"#),
+        None => return to_c_string(&error_json("invalid_argument", "invalid approver")),
     };
 
-    let stage: Stage = match serde_json::from_str(&format!(r#""{}""#, stage_name)) {
-        Ok(p) => p,
-        Err(_) => return to_c_string(r#"{"error": "unknown stage"}"#),
+    let stage: Stage = match stage_name.parse() {
+        Ok(stage) => stage,
+        Err(e) => return to_c_string(&error_json("invalid_stage", e)),
     };
 
     let engine = unsafe { &mut *ptr };
     match engine.approve_gate(stage, &by) {
-        Ok(()) => to_c_string(r#"{"success": true}"#),
-        Err(e) => to_c_string(&format!(r#"{{"error": "{}"}}"#, e)),
+        Ok(advance) => to_c_string(&approval_json(advance)),
+        Err(e) => to_c_string(&workflow_error_json(&e)),
+    }
+}
+
+/// Export a stage's gate as a standalone approval request
+#[no_mangle]
+pub extern "C" fn workflow_engine_gate_approval_request(
+    ptr: *const WorkflowEngine,
+    stage_str: *const c_char,
+) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string(&error_json("null_pointer", "null engine pointer"));
+    }
+
+    let stage_name = match from_c_string(stage_str) {
+        Some(s) => s,
+        None => return to_c_string(&error_json("invalid_stage", "invalid stage")),
+    };
+
+    let stage: Stage = match stage_name.parse() {
+        Ok(stage) => stage,
+        Err(e) => return to_c_string(&error_json("invalid_stage", e)),
+    };
+
+    let engine = unsafe { &*ptr };
+    match engine.get_gate(stage) {
+        Some(gate) => match serde_json::to_string(&gate.to_approval_request()) {
+            Ok(json) => to_c_string(&json),
+            Err(e) => to_c_string(&error_json("serialization_error", e)),
+        },
+        None => to_c_string(&workflow_error_json(&WorkflowError::GateNotFound(stage))),
+    }
+}
+
+/// Apply an external approval decision
+#[no_mangle]
+pub extern "C" fn workflow_engine_apply_approval(
+    ptr: *mut WorkflowEngine,
+    response_json: *const c_char,
+) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string(&error_json("null_pointer", "null engine pointer"));
+    }
+
+    let json_str = match from_c_string(response_json) {
+        Some(s) => s,
+        None => return to_c_string(&error_json("invalid_argument", "invalid approval JSON")),
+    };
+
+    let response: ApprovalResponse = match serde_json::from_str(&json_str) {
+        Ok(r) => r,
+        Err(e) => return to_c_string(&error_json("parse_error", format!("parse error: {}", e))),
+    };
+
+    let engine = unsafe { &mut *ptr };
+    match engine.apply_approval(&response) {
+        Ok(advance) => to_c_string(&approval_json(advance)),
+        Err(e) => to_c_string(&workflow_error_json(&e)),
     }
 }
 
@@ -324,22 +513,21 @@ pub extern "C" fn knowledge_manager_check_budget(
     worker_id: *const c_char,
 ) -> *mut c_char {
     if ptr.is_null() {
-        return to_c_string(r#"{"error": "null manager pointer"}"#);
+        return to_c_string(&error_json("null_pointer", "null manager pointer"));
     }
 
     let id = match from_c_string(worker_id) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid worker ID"}"#),
+        None => return to_c_string(&error_json("invalid_argument", "invalid worker ID")),
     };
 
     let manager = unsafe { &*ptr };
     match manager.check_budget(&id) {
         Some(status) => {
-            let (status_str, remaining) = match status {
-                BudgetStatus::Healthy => ("healthy", None),
-                BudgetStatus::Warning { remaining } => ("warning", Some(remaining)),
-                BudgetStatus::Critical { remaining } => ("critical", Some(remaining)),
-                BudgetStatus::Exceeded => ("exceeded", None),
+            let status_str = budget_status_str(&status);
+            let remaining = match status {
+                BudgetStatus::Warning { remaining } | BudgetStatus::Critical { remaining } => Some(remaining),
+                BudgetStatus::Healthy | BudgetStatus::Exceeded => None,
             };
 
             if let Some(r) = remaining {
@@ -348,35 +536,163 @@ pub extern "C" fn knowledge_manager_check_budget(
                 to_c_string(&format!(r#"{{"status": "{}"}}"#, status_str))
             }
         }
-        None => to_c_string(r#"{"error": "worker not found"}"#),
+        None => to_c_string(&error_json("worker_not_found", "worker not found")),
+    }
+}
+
+/// Full budget numbers for a worker's progress bar.
+/// Returns `{"used", "budget", "remaining", "usage_ratio", "status"}`.
+#[no_mangle]
+pub extern "C" fn knowledge_manager_budget_detail(
+    ptr: *const KnowledgeManager,
+    worker_id: *const c_char,
+) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string(&error_json("null_pointer", "null manager pointer"));
+    }
+
+    let id = match from_c_string(worker_id) {
+        Some(s) => s,
+        None => return to_c_string(&error_json("invalid_argument", "invalid worker ID")),
+    };
+
+    let manager = unsafe { &*ptr };
+    match manager.get_budget(&id) {
+        Some(budget) => {
+            let json = serde_json::json!({
+                "used": budget.used,
+                "budget": budget.budget,
+                "remaining": budget.remaining(),
+                "usage_ratio": budget.usage_ratio(),
+                "status": budget_status_str(&budget.status()),
+            });
+            to_c_string(&json.to_string())
+        }
+        None => to_c_string(&error_json("worker_not_found", "worker not found")),
     }
 }
 
-/// Validate a handoff
+/// Token totals across every worker for the mission cost headline.
+/// Returns `{"used", "budget", "status"}`.
+#[no_mangle]
+pub extern "C" fn knowledge_manager_mission_budget(ptr: *const KnowledgeManager) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string(&error_json("null_pointer", "null manager pointer"));
+    }
+
+    let manager = unsafe { &*ptr };
+    let json = serde_json::json!({
+        "used": manager.total_used(),
+        "budget": manager.total_budget(),
+        "status": budget_status_str(&manager.mission_status()),
+    });
+    to_c_string(&json.to_string())
+}
+
+fn budget_status_str(status: &BudgetStatus) -> &'static str {
+    match status {
+        BudgetStatus::Healthy => "healthy",
+        BudgetStatus::Warning { .. } => "warning",
+        BudgetStatus::Critical { .. } => "critical",
+        BudgetStatus::Exceeded => "exceeded",
+    }
+}
+
+/// Page through stored findings.
+/// Returns `{"findings": [...], "offset": N, "limit": N, "total": N}`.
+#[no_mangle]
+pub extern "C" fn knowledge_manager_findings_page(
+    ptr: *const KnowledgeManager,
+    offset: usize,
+    limit: usize,
+) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string(&error_json("null_pointer", "null manager pointer"));
+    }
+
+    let manager = unsafe { &*ptr };
+    let json = serde_json::json!({
+        "findings": manager.findings_page(offset, limit),
+        "offset": offset,
+        "limit": limit,
+        "total": manager.findings_count(),
+    });
+    to_c_string(&json.to_string())
+}
+
+/// Validate a handoff. Always returns
+/// `{"valid": bool, "errors": [{"code", "message"}], "warnings": [...]}`.
 #[no_mangle]
 pub extern "C" fn knowledge_manager_validate_handoff(
     ptr: *const KnowledgeManager,
     handoff_json: *const c_char,
 ) -> *mut c_char {
     if ptr.is_null() {
-        return to_c_string(r#"{"error": "null manager pointer"}"#);
+        return to_c_string(&error_json("null_pointer", "null manager pointer"));
     }
 
     let json_str = match from_c_string(handoff_json) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid handoff JSON"}"#),
+        None => return to_c_string(&error_json("invalid_argument", "invalid handoff JSON")),
     };
 
     let handoff: Handoff = match serde_json::from_str(&json_str) {
         Ok(h) => h,
-        Err(e) => return to_c_string(&format!(r#"{{"error": "parse error: {}"}}"#, e)),
+        Err(e) => return to_c_string(&error_json("parse_error", format!("parse error: {}", e))),
     };
 
     let manager = unsafe { &*ptr };
-    match manager.validate_handoff(&handoff) {
-        Ok(()) => std::ptr::null_mut(), // null means valid
-        Err(e) => to_c_string(&format!(r#"{{"error": "{}"}}"#, e)),
-    }
+    let report = manager.validate_handoff_report(&handoff);
+    let errors: Vec<serde_json::Value> = report.errors.iter()
+        .map(|e| serde_json::json!({ "code": e.code(), "message": e.to_string() }))
+        .collect();
+    let result = serde_json::json!({
+        "valid": report.is_valid(),
+        "errors": errors,
+        "warnings": report.warnings,
+    });
+    to_c_string(&result.to_string())
+}
+
+/// Compile a checkpoint plus optional successor context into a markdown
+/// briefing trimmed to `max_tokens` (0 = no limit).
+/// `successor_json` may be null. Returns the markdown, or an error JSON
+/// object if either input can't be parsed.
+#[no_mangle]
+pub extern "C" fn knowledge_compile_briefing(
+    checkpoint_json: *const c_char,
+    successor_json: *const c_char,
+    max_tokens: usize,
+) -> *mut c_char {
+    let checkpoint_str = match from_c_string(checkpoint_json) {
+        Some(s) => s,
+        None => return to_c_string(&error_json("invalid_argument", "invalid checkpoint JSON")),
+    };
+
+    let checkpoint = match Checkpoint::from_json(&checkpoint_str) {
+        Ok(c) => c,
+        Err(e) => return to_c_string(&error_json("parse_error", e)),
+    };
+
+    let successor: Option<SuccessorContext> = if successor_json.is_null() {
+        None
+    } else {
+        let successor_str = match from_c_string(successor_json) {
+            Some(s) => s,
+            None => return to_c_string(&error_json("invalid_argument", "invalid successor JSON")),
+        };
+        match serde_json::from_str(&successor_str) {
+            Ok(ctx) => Some(ctx),
+            Err(e) => return to_c_string(&error_json("parse_error", format!("successor parse error: {}", e))),
+        }
+    };
+
+    // Building the cl100k tables is slow, so share one counter across calls
+    static COUNTER: OnceLock<TokenCounter> = OnceLock::new();
+    let counter = COUNTER.get_or_init(TokenCounter::new_or_approximate);
+    let briefing = CheckpointCompiler::compile_within_budget(&checkpoint, successor.as_ref(), max_tokens, counter);
+
+    to_c_string(&briefing)
 }
 
 // ============================================================================
@@ -395,6 +711,29 @@ pub extern "C" fn health_monitor_with_thresholds(stuck_ms: u64, idle_ms: u64) ->
     Box::into_raw(Box::new(HealthMonitor::with_thresholds(stuck_ms, idle_ms)))
 }
 
+/// Change thresholds on a running HealthMonitor, keeping worker state
+#[no_mangle]
+pub extern "C" fn health_monitor_set_thresholds(ptr: *mut HealthMonitor, stuck_ms: u64, idle_ms: u64) {
+    if ptr.is_null() {
+        return;
+    }
+
+    let monitor = unsafe { &mut *ptr };
+    monitor.set_thresholds(stuck_ms, idle_ms);
+}
+
+/// Get current thresholds as JSON: {"stuck_ms", "idle_ms"}
+#[no_mangle]
+pub extern "C" fn health_monitor_get_thresholds(ptr: *const HealthMonitor) -> *mut c_char {
+    if ptr.is_null() {
+        return to_c_string(&error_json("null_pointer", "null monitor pointer"));
+    }
+
+    let monitor = unsafe { &*ptr };
+    let (stuck_ms, idle_ms) = monitor.thresholds();
+    to_c_string(&serde_json::json!({ "stuck_ms": stuck_ms, "idle_ms": idle_ms }).to_string())
+}
+
 /// Free a HealthMonitor
 #[no_mangle]
 pub extern "C" fn health_monitor_free(ptr: *mut HealthMonitor) {
@@ -481,34 +820,66 @@ pub extern "C" fn health_monitor_mark_tool_call(
     monitor.mark_tool_call(&id);
 }
 
-/// Check health status for a worker
+/// Add tokens consumed by a worker, for per-worker burn reporting
+#[no_mangle]
+pub extern "C" fn health_monitor_mark_tokens(
+    ptr: *mut HealthMonitor,
+    worker_id: *const c_char,
+    tokens: u64,
+) {
+    if ptr.is_null() {
+        return;
+    }
+
+    let id = match from_c_string(worker_id) {
+        Some(s) => s,
+        None => return,
+    };
+
+    let monitor = unsafe { &mut *ptr };
+    monitor.mark_tokens(&id, tokens);
+}
+
+/// Check health status for a worker, with `tokens_consumed`. Includes
+/// `since_tool_call_ms` once the worker has made a tool call.
 #[no_mangle]
 pub extern "C" fn health_monitor_check_health(
     ptr: *const HealthMonitor,
     worker_id: *const c_char,
 ) -> *mut c_char {
     if ptr.is_null() {
-        return to_c_string(r#"{"error": "null monitor pointer"}"#);
+        return to_c_string(&error_json("null_pointer", "null monitor pointer"));
     }
 
     let id = match from_c_string(worker_id) {
         Some(s) => s,
-        None => return to_c_string(r#"{"error": "invalid worker ID"}"#),
+        None => return to_c_string(&error_json("invalid_argument", "invalid worker ID")),
     };
 
     let monitor = unsafe { &*ptr };
     match monitor.check_health(&id) {
         Some(status) => {
-            let json = match status {
-                HealthStatus::Healthy => r#"{"status": "healthy"}"#.to_string(),
-                HealthStatus::Idle { since_ms } => format!(r#"{{"status": "idle", "since_ms": {}}}"#, since_ms),
-                HealthStatus::Stuck { since_ms } => format!(r#"{{"status": "stuck", "since_ms": {}}}"#, since_ms),
-                HealthStatus::Unresponsive => r#"{"status": "unresponsive"}"#.to_string(),
-                HealthStatus::Dead => r#"{"status": "dead"}"#.to_string(),
+            let mut json = match status {
+                HealthStatus::Healthy => serde_json::json!({ "status": "healthy" }),
+                HealthStatus::Idle { since_ms } => serde_json::json!({ "status": "idle", "since_ms": since_ms }),
+                HealthStatus::Stuck { since_ms, since_timestamp } => serde_json::json!({
+                    "status": "stuck",
+                    "since_ms": since_ms,
+                    "since_timestamp": since_timestamp,
+                }),
+                HealthStatus::Unresponsive => serde_json::json!({ "status": "unresponsive" }),
+                HealthStatus::Dead => serde_json::json!({ "status": "dead" }),
             };
-            to_c_string(&json)
+            if let Some(worker) = monitor.get_worker(&id) {
+                json["tokens_consumed"] = serde_json::json!(worker.tokens_consumed);
+                // Tool-call recency, once the worker has made a call
+                if let Some(since) = worker.time_since_tool_call() {
+                    json["since_tool_call_ms"] = serde_json::json!(since);
+                }
+            }
+            to_c_string(&json.to_string())
         }
-        None => to_c_string(r#"{"error": "worker not found"}"#),
+        None => to_c_string(&error_json("worker_not_found", "worker not found")),
     }
 }
 
@@ -539,6 +910,9 @@ mod tests {
 
         let stage = workflow_engine_current_stage(engine);
         assert!(!stage.is_null());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(stage) }.to_str().unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!({ "stage": "discovery" }));
 
         // Clean up
         missioncontrol_free_string(stage);
@@ -557,6 +931,233 @@ mod tests {
         knowledge_manager_free(manager);
     }
 
+    #[test]
+    fn test_knowledge_manager_budget_detail() {
+        let manager = knowledge_manager_new();
+        let worker_id = CString::new("worker-1").unwrap();
+        knowledge_manager_create_budget(manager, worker_id.as_ptr(), 1000);
+        unsafe { &mut *manager }.record_usage("worker-1", 600);
+
+        let result = knowledge_manager_budget_detail(manager, worker_id.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["used"], 600);
+        assert_eq!(json["budget"], 1000);
+        assert_eq!(json["remaining"], 400);
+        assert!((json["usage_ratio"].as_f64().unwrap() - 0.6).abs() < 1e-6);
+        assert_eq!(json["status"], "warning");
+        missioncontrol_free_string(result);
+
+        let missing = CString::new("missing").unwrap();
+        let result = knowledge_manager_budget_detail(manager, missing.as_ptr());
+        assert!(unsafe { CStr::from_ptr(result) }.to_str().unwrap().contains("worker not found"));
+        missioncontrol_free_string(result);
+
+        let result = knowledge_manager_mission_budget(manager);
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["used"], 600);
+        assert_eq!(json["budget"], 1000);
+        assert_eq!(json["status"], "warning");
+        missioncontrol_free_string(result);
+        knowledge_manager_free(manager);
+    }
+
+    #[test]
+    fn test_knowledge_manager_findings_page() {
+        let manager = knowledge_manager_new();
+        for i in 0..3 {
+            unsafe { &mut *manager }.store_finding(knowledge::Finding::discovery(format!("Finding {}", i)));
+        }
+
+        let result = knowledge_manager_findings_page(manager, 1, 5);
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["total"], 3);
+        assert_eq!(json["findings"].as_array().unwrap().len(), 2);
+        assert_eq!(json["findings"][0]["summary"], "Finding 1");
+
+        missioncontrol_free_string(result);
+        knowledge_manager_free(manager);
+    }
+
+    #[test]
+    fn test_validate_handoff_report() {
+        let manager = knowledge_manager_new();
+
+        let valid = CString::new(serde_json::to_string(&Handoff::complete("task-1", "worker-1")).unwrap()).unwrap();
+        let result = knowledge_manager_validate_handoff(manager, valid.as_ptr());
+        assert!(!result.is_null());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["valid"], true);
+        assert_eq!(json["errors"].as_array().unwrap().len(), 0);
+        assert_eq!(json["warnings"].as_array().unwrap().len(), 1);
+        missioncontrol_free_string(result);
+
+        let invalid = CString::new(serde_json::to_string(&Handoff::complete("", "")).unwrap()).unwrap();
+        let result = knowledge_manager_validate_handoff(manager, invalid.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["valid"], false);
+        assert_eq!(json["errors"].as_array().unwrap().len(), 2);
+        assert_eq!(json["errors"][0]["code"], "missing_field");
+        missioncontrol_free_string(result);
+
+        knowledge_manager_free(manager);
+    }
+
+    #[test]
+    fn test_compile_briefing() {
+        let checkpoint = CString::new(
+            r#"{"id": "cp-1", "stage": "design", "created_at": 1, "tasks_snapshot": [],
+                "findings_snapshot": [], "decisions": ["Use REST"]}"#,
+        ).unwrap();
+        let successor = CString::new(
+            r#"{"key_decisions": [], "gotchas": ["Auth is flaky"], "recommended_approach": null}"#,
+        ).unwrap();
+
+        let result = knowledge_compile_briefing(checkpoint.as_ptr(), successor.as_ptr(), 0);
+        let briefing = unsafe { CStr::from_ptr(result) }.to_str().unwrap().to_string();
+        assert!(briefing.contains("## Stage: design"));
+        assert!(briefing.contains("Use REST"));
+        assert!(briefing.contains("Auth is flaky"));
+        missioncontrol_free_string(result);
+
+        let result = knowledge_compile_briefing(checkpoint.as_ptr(), std::ptr::null(), 0);
+        let text = unsafe { CStr::from_ptr(result) }.to_str().unwrap().to_string();
+        assert!(!text.contains("Predecessor"));
+        missioncontrol_free_string(result);
+
+        let bad = CString::new("not json").unwrap();
+        let result = knowledge_compile_briefing(bad.as_ptr(), std::ptr::null(), 0);
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["error_code"], "parse_error");
+        missioncontrol_free_string(result);
+    }
+
+    #[test]
+    fn test_errors_are_escaped_json_with_code() {
+        let engine = workflow_engine_new();
+
+        let stage = CString::new(r#"bad"stage"#).unwrap();
+        let result = workflow_engine_check_gate(engine, stage.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["error_code"], "invalid_stage");
+        missioncontrol_free_string(result);
+
+        let task = CString::new(r#"{"id": "a"b"#).unwrap();
+        let result = workflow_engine_create_task(engine, task.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["error_code"], "parse_error");
+        missioncontrol_free_string(result);
+
+        let result = workflow_engine_create_task(std::ptr::null_mut(), task.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["error_code"], "null_pointer");
+        missioncontrol_free_string(result);
+
+        workflow_engine_free(engine);
+    }
+
+    #[test]
+    fn test_can_approve_gate() {
+        let engine = workflow_engine_new();
+        let stage = CString::new("goal").unwrap();
+
+        let result = workflow_engine_can_approve_gate(engine, stage.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["can_approve"], false);
+        assert_eq!(json["reasons"][0], "Previous stage gate not open: discovery");
+
+        missioncontrol_free_string(result);
+        workflow_engine_free(engine);
+    }
+
+    #[test]
+    fn test_all_gate_statuses() {
+        let engine = workflow_engine_new();
+        unsafe { &mut *engine }.approve_gate(Stage::Discovery, "alice").unwrap();
+
+        let result = workflow_engine_all_gate_statuses(engine);
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 10);
+        assert_eq!(json[0]["stage"], "discovery");
+        assert_eq!(json[0]["status"], "open");
+        assert_eq!(json[9]["stage"], "release");
+        assert_eq!(json[9]["status"], "closed");
+        missioncontrol_free_string(result);
+
+        workflow_engine_free(engine);
+    }
+
+    #[test]
+    fn test_gate_approval_round_trip() {
+        let engine = workflow_engine_new();
+        let stage = CString::new("discovery").unwrap();
+
+        let result = workflow_engine_gate_approval_request(engine, stage.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["gate_id"], "gate-discovery");
+        assert_eq!(json["missing"].as_array().unwrap().len(), 2);
+        missioncontrol_free_string(result);
+
+        let response = CString::new(r#"{"gate_id": "gate-discovery", "stage": "discovery", "revision": 0, "approved": true, "approved_by": "alice"}"#).unwrap();
+        let result = workflow_engine_apply_approval(engine, response.as_ptr());
+        assert!(unsafe { CStr::from_ptr(result) }.to_str().unwrap().contains("success"));
+        missioncontrol_free_string(result);
+        assert_eq!(unsafe { &*engine }.check_gate(Stage::Discovery), GateStatus::Open);
+
+        workflow_engine_free(engine);
+    }
+
+    #[test]
+    fn test_approve_gate_auto_advance() {
+        let engine = workflow_engine_new();
+        workflow_engine_set_auto_advance(engine, true);
+        if let Some(gate) = unsafe { &mut *engine }.get_gate_mut(Stage::Discovery) {
+            for i in 0..gate.criteria.len() {
+                gate.satisfy_criterion(i);
+            }
+        }
+
+        let stage = CString::new("discovery").unwrap();
+        let by = CString::new("alice").unwrap();
+        let result = workflow_engine_approve_gate(engine, stage.as_ptr(), by.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["success"], true);
+        assert_eq!(json["transition"]["from"], "discovery");
+        assert_eq!(json["transition"]["to"], "goal");
+        missioncontrol_free_string(result);
+        assert_eq!(unsafe { &*engine }.current_stage(), Stage::Goal);
+
+        workflow_engine_free(engine);
+    }
+
+    #[test]
+    fn test_workflow_error_code() {
+        let engine = workflow_engine_new();
+        let task_id = CString::new("missing").unwrap();
+        let status = CString::new(r#""done""#).unwrap();
+
+        let result = workflow_engine_update_task_status(engine, task_id.as_ptr(), status.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["error_code"], "task_not_found");
+        assert_eq!(json["error"], "Task not found: missing");
+
+        missioncontrol_free_string(result);
+        workflow_engine_free(engine);
+    }
+
     #[test]
     fn test_health_monitor_lifecycle() {
         let monitor = health_monitor_new();
@@ -567,7 +1168,41 @@ mod tests {
 
         let health = health_monitor_check_health(monitor, worker_id.as_ptr());
         assert!(!health.is_null());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(health) }.to_str().unwrap()).unwrap();
+        assert!(json.get("since_tool_call_ms").is_none());
+        assert_eq!(json["tokens_consumed"], 0);
+        missioncontrol_free_string(health);
 
+        health_monitor_mark_tokens(monitor, worker_id.as_ptr(), 120);
+        health_monitor_mark_tool_call(monitor, worker_id.as_ptr());
+        let health = health_monitor_check_health(monitor, worker_id.as_ptr());
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(health) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["status"], "healthy");
+        assert!(json["since_tool_call_ms"].as_u64().is_some());
+        assert_eq!(json["tokens_consumed"], 120);
+
+        missioncontrol_free_string(health);
+        health_monitor_free(monitor);
+    }
+
+    #[test]
+    fn test_health_monitor_thresholds() {
+        let monitor = health_monitor_with_thresholds(5000, 2000);
+        let worker_id = CString::new("worker-1").unwrap();
+        health_monitor_register_worker(monitor, worker_id.as_ptr());
+
+        health_monitor_set_thresholds(monitor, 9000, 3000);
+        let result = health_monitor_get_thresholds(monitor);
+        let json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(result) }.to_str().unwrap()).unwrap();
+        assert_eq!(json["stuck_ms"], 9000);
+        assert_eq!(json["idle_ms"], 3000);
+        missioncontrol_free_string(result);
+
+        let health = health_monitor_check_health(monitor, worker_id.as_ptr());
+        assert!(!unsafe { CStr::from_ptr(health) }.to_str().unwrap().contains("error"));
         missioncontrol_free_string(health);
         health_monitor_free(monitor);
     }
@@ -1,4 +1,12 @@
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use workflow::Stage;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BudgetError {
+    #[error("Invalid thresholds: need 0 <= warning ({warning}) < critical ({critical}) <= 1")]
+    InvalidThresholds { warning: f32, critical: f32 },
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -9,13 +17,34 @@ pub enum BudgetStatus {
     Exceeded,
 }
 
+/// Usage captured by `TokenBudget::snapshot`, for rolling back
+/// speculative spending with `TokenBudget::restore`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetSnapshot {
+    pub used: usize,
+    pub reserved: usize,
+    history_len: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenBudget {
     pub worker_id: String,
     pub budget: usize,
     pub used: usize,
+    /// Tokens set aside for planned work that has not been spent yet.
+    #[serde(default)]
+    pub reserved: usize,
     pub warning_threshold: f32,
     pub critical_threshold: f32,
+    /// `(unix_secs, cumulative used)` samples, recorded only when enabled
+    /// with `with_history`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<(u64, usize)>,
+    #[serde(default)]
+    track_history: bool,
+    /// A single `record_and_status` above this many tokens is a spike.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spike_threshold: Option<usize>,
 }
 
 impl TokenBudget {
@@ -24,30 +53,169 @@ impl TokenBudget {
             worker_id: worker_id.to_string(),
             budget,
             used: 0,
+            reserved: 0,
             warning_threshold: 0.5,
             critical_threshold: 0.75,
+            history: Vec::new(),
+            track_history: false,
+            spike_threshold: None,
         }
     }
 
+    /// Budget with the default token allowance for a stage. Exploratory
+    /// stages get more room; narrow stages like goal and release less.
+    pub fn default_for_stage(worker_id: &str, stage: Stage) -> Self {
+        let budget = match stage {
+            Stage::Discovery => 40000,
+            Stage::Goal => 10000,
+            Stage::Requirements => 20000,
+            Stage::Planning => 20000,
+            Stage::Design => 30000,
+            Stage::Implement => 50000,
+            Stage::Verify => 30000,
+            Stage::Validate => 25000,
+            Stage::Document => 20000,
+            Stage::Release => 10000,
+        };
+        Self::new(worker_id, budget)
+    }
+
+    /// Set thresholds without validation. Prefer `try_with_thresholds`.
     pub fn with_thresholds(mut self, warning: f32, critical: f32) -> Self {
         self.warning_threshold = warning;
         self.critical_threshold = critical;
         self
     }
 
+    /// Set thresholds, requiring `0 <= warning < critical <= 1`.
+    pub fn try_with_thresholds(self, warning: f32, critical: f32) -> Result<Self, BudgetError> {
+        // Written so that NaN fails the check
+        let valid = warning >= 0.0 && warning < critical && critical <= 1.0;
+        if !valid {
+            return Err(BudgetError::InvalidThresholds { warning, critical });
+        }
+        Ok(self.with_thresholds(warning, critical))
+    }
+
+    /// Keep a usage sample on every `record`/`commit` for burn-rate analysis.
+    pub fn with_history(mut self) -> Self {
+        self.track_history = true;
+        self.sample_history();
+        self
+    }
+
+    pub fn with_spike_threshold(mut self, tokens: usize) -> Self {
+        self.spike_threshold = Some(tokens);
+        self
+    }
+
     pub fn record(&mut self, tokens: usize) {
         self.used += tokens;
+        self.sample_history();
+    }
+
+    /// Record `tokens` and report the resulting status, along with the
+    /// amount when this one record exceeds the spike threshold.
+    pub fn record_and_status(&mut self, tokens: usize) -> (BudgetStatus, Option<usize>) {
+        self.record(tokens);
+        let spike = self.spike_threshold
+            .filter(|threshold| tokens > *threshold)
+            .map(|_| tokens);
+        (self.status(), spike)
+    }
+
+    /// Set tokens aside for a planned step so concurrent planners see them as taken.
+    pub fn reserve(&mut self, tokens: usize) {
+        self.reserved += tokens;
+    }
+
+    /// Return previously reserved tokens that will not be spent.
+    pub fn release(&mut self, tokens: usize) {
+        self.reserved = self.reserved.saturating_sub(tokens);
+    }
+
+    /// Move reserved tokens into `used` once they have actually been spent.
+    /// Spending beyond the reservation is still recorded in full.
+    pub fn commit(&mut self, tokens: usize) {
+        self.reserved = self.reserved.saturating_sub(tokens);
+        self.used += tokens;
+        self.sample_history();
+    }
+
+    /// Fold another budget for the same worker into this one: usage and
+    /// reservations are summed, and the larger allowance is kept.
+    pub fn merge(&mut self, other: &TokenBudget) {
+        self.used += other.used;
+        self.reserved += other.reserved;
+        self.budget = self.budget.max(other.budget);
+        self.sample_history();
+    }
+
+    pub fn snapshot(&self) -> BudgetSnapshot {
+        BudgetSnapshot {
+            used: self.used,
+            reserved: self.reserved,
+            history_len: self.history.len(),
+        }
+    }
+
+    /// Roll usage, reservations and history back to `snapshot`. The
+    /// allowance and thresholds are left as they are.
+    pub fn restore(&mut self, snapshot: BudgetSnapshot) {
+        self.used = snapshot.used;
+        self.reserved = snapshot.reserved;
+        self.history.truncate(snapshot.history_len);
+    }
+
+    fn sample_history(&mut self) {
+        if !self.track_history {
+            return;
+        }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.history.push((now, self.used));
+    }
+
+    /// Average tokens spent per minute across the recorded history.
+    /// Zero without history or before any time has elapsed.
+    pub fn burn_rate_per_min(&self) -> f32 {
+        let (first, last) = match (self.history.first(), self.history.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0.0,
+        };
+        let elapsed = last.0.saturating_sub(first.0);
+        if elapsed == 0 {
+            return 0.0;
+        }
+        last.1.saturating_sub(first.1) as f32 * 60.0 / elapsed as f32
+    }
+
+    /// Minutes until the budget runs out at the current burn rate, or
+    /// `None` if nothing is being spent.
+    pub fn minutes_until_exhausted(&self) -> Option<f32> {
+        let rate = self.burn_rate_per_min();
+        if rate <= 0.0 {
+            return None;
+        }
+        Some(self.remaining() as f32 / rate)
+    }
+
+    /// Tokens counted against the budget: spent plus reserved.
+    pub fn consumed(&self) -> usize {
+        self.used + self.reserved
     }
 
     pub fn remaining(&self) -> usize {
-        self.budget.saturating_sub(self.used)
+        self.budget.saturating_sub(self.consumed())
     }
 
     pub fn usage_ratio(&self) -> f32 {
         if self.budget == 0 {
             return 1.0;
         }
-        self.used as f32 / self.budget as f32
+        self.consumed() as f32 / self.budget as f32
     }
 
     pub fn status(&self) -> BudgetStatus {
@@ -79,6 +247,21 @@ mod tests {
         assert_eq!(budget.remaining(), 20000);
     }
 
+    #[test]
+    fn test_budget_merge() {
+        let mut budget = TokenBudget::new("worker-1", 20000);
+        budget.record(5000);
+        let mut other = TokenBudget::new("Worker-1", 30000);
+        other.record(2000);
+        other.reserve(1000);
+
+        budget.merge(&other);
+        assert_eq!(budget.used, 7000);
+        assert_eq!(budget.reserved, 1000);
+        assert_eq!(budget.budget, 30000);
+        assert_eq!(budget.worker_id, "worker-1");
+    }
+
     #[test]
     fn test_budget_recording() {
         let mut budget = TokenBudget::new("worker-1", 20000);
@@ -120,4 +303,127 @@ mod tests {
         budget.record(25000);
         assert_eq!(budget.status(), BudgetStatus::Exceeded);
     }
+
+    #[test]
+    fn test_record_and_status_flags_spikes() {
+        let mut budget = TokenBudget::new("worker-1", 100000).with_spike_threshold(20000);
+        assert_eq!(budget.record_and_status(5000), (BudgetStatus::Healthy, None));
+        assert_eq!(budget.record_and_status(50000), (BudgetStatus::Warning { remaining: 45000 }, Some(50000)));
+        assert_eq!(budget.used, 55000);
+
+        // A spike that crosses the budget still reports Exceeded
+        assert_eq!(budget.record_and_status(60000), (BudgetStatus::Exceeded, Some(60000)));
+
+        let mut unlimited = TokenBudget::new("worker-2", 100000);
+        assert_eq!(unlimited.record_and_status(50000), (BudgetStatus::Warning { remaining: 50000 }, None));
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let mut budget = TokenBudget::new("worker-1", 1000).with_history();
+        budget.record(100);
+        let snapshot = budget.snapshot();
+
+        budget.reserve(300);
+        budget.record(500);
+        assert_eq!(budget.status(), BudgetStatus::Critical { remaining: 100 });
+
+        budget.restore(snapshot);
+        assert_eq!(budget.used, 100);
+        assert_eq!(budget.reserved, 0);
+        assert_eq!(budget.history.len(), 2);
+        assert_eq!(budget.status(), BudgetStatus::Healthy);
+    }
+
+    #[test]
+    fn test_budget_reserve_counts_as_consumed() {
+        let mut budget = TokenBudget::new("worker-1", 20000);
+        budget.record(8000);
+        budget.reserve(4000); // 60% once reserved
+        assert_eq!(budget.remaining(), 8000);
+        assert_eq!(budget.status(), BudgetStatus::Warning { remaining: 8000 });
+    }
+
+    #[test]
+    fn test_budget_release() {
+        let mut budget = TokenBudget::new("worker-1", 20000);
+        budget.reserve(5000);
+        budget.release(3000);
+        assert_eq!(budget.reserved, 2000);
+        budget.release(10000);
+        assert_eq!(budget.reserved, 0);
+        assert_eq!(budget.remaining(), 20000);
+    }
+
+    #[test]
+    fn test_budget_commit_moves_reserved_to_used() {
+        let mut budget = TokenBudget::new("worker-1", 20000);
+        budget.reserve(5000);
+        budget.commit(3000);
+        assert_eq!(budget.reserved, 2000);
+        assert_eq!(budget.used, 3000);
+        assert_eq!(budget.remaining(), 15000);
+
+        // Overspend beyond the reservation is still recorded
+        budget.commit(4000);
+        assert_eq!(budget.reserved, 0);
+        assert_eq!(budget.used, 7000);
+    }
+
+    #[test]
+    fn test_budget_deserializes_without_reserved() {
+        let json = r#"{"worker_id":"w","budget":100,"used":10,"warning_threshold":0.5,"critical_threshold":0.75}"#;
+        let budget: TokenBudget = serde_json::from_str(json).unwrap();
+        assert_eq!(budget.reserved, 0);
+    }
+
+    #[test]
+    fn test_default_for_stage() {
+        let discovery = TokenBudget::default_for_stage("worker-1", Stage::Discovery);
+        let release = TokenBudget::default_for_stage("worker-1", Stage::Release);
+        assert_eq!(discovery.worker_id, "worker-1");
+        assert!(discovery.budget > release.budget);
+
+        for stage in Stage::all() {
+            assert!(TokenBudget::default_for_stage("w", *stage).budget > 0);
+        }
+    }
+
+    #[test]
+    fn test_try_with_thresholds() {
+        let budget = TokenBudget::new("worker-1", 100).try_with_thresholds(0.6, 0.9).unwrap();
+        assert_eq!(budget.warning_threshold, 0.6);
+        assert_eq!(budget.critical_threshold, 0.9);
+
+        for (warning, critical) in [(0.9, 0.5), (0.5, 0.5), (-0.1, 0.5), (0.5, 1.5), (f32::NAN, 0.5)] {
+            assert!(
+                TokenBudget::new("worker-1", 100).try_with_thresholds(warning, critical).is_err(),
+                "accepted {} / {}", warning, critical
+            );
+        }
+    }
+
+    #[test]
+    fn test_history_is_opt_in() {
+        let mut budget = TokenBudget::new("worker-1", 20000);
+        budget.record(100);
+        assert!(budget.history.is_empty());
+        assert_eq!(budget.burn_rate_per_min(), 0.0);
+
+        let mut budget = TokenBudget::new("worker-1", 20000).with_history();
+        budget.record(100);
+        budget.commit(50);
+        assert_eq!(budget.history.len(), 3);
+        assert_eq!(budget.history.last().unwrap().1, 150);
+    }
+
+    #[test]
+    fn test_burn_rate_per_min() {
+        let mut budget = TokenBudget::new("worker-1", 20000);
+        budget.used = 6000;
+        budget.history = vec![(1_000, 0), (1_060, 2000), (1_120, 6000)];
+
+        assert_eq!(budget.burn_rate_per_min(), 3000.0);
+        assert_eq!(budget.minutes_until_exhausted(), Some(14000.0 / 3000.0));
+    }
 }
@@ -1,9 +1,32 @@
+use std::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use workflow::{Stage, Task};
-use crate::handoff::Finding;
+use crate::handoff::{Finding, FindingType, SuccessorContext};
+use crate::manager::BriefingInputs;
+use crate::tokens::TokenCounter;
 
+/// Schema version written by this build. Files without a version predate
+/// versioning and read as 0.
+pub const CHECKPOINT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("Checkpoint parse error: {0}")]
+    Parse(String),
+
+    #[error("Checkpoint schema version {found} is newer than supported version {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("Snapshot has tasks past stage {}: {}", stage.as_str(), tasks.join(", "))]
+    TasksPastStage { stage: Stage, tasks: Vec<String> },
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Checkpoint {
+    #[serde(default)]
+    pub schema_version: u32,
     pub id: String,
     pub stage: Stage,
     pub created_at: u64,
@@ -14,6 +37,10 @@ pub struct Checkpoint {
     pub session_id: Option<String>,
     #[serde(default)]
     pub blockers: Vec<String>,
+    /// `findings_snapshot` holds only findings new since the previous
+    /// checkpoint; see `KnowledgeManager::full_findings_snapshot`.
+    #[serde(default)]
+    pub findings_incremental: bool,
 }
 
 impl Checkpoint {
@@ -24,6 +51,7 @@ impl Checkpoint {
             .as_secs();
 
         Self {
+            schema_version: CHECKPOINT_SCHEMA_VERSION,
             id: id.into(),
             stage,
             created_at: now,
@@ -32,6 +60,7 @@ impl Checkpoint {
             decisions: Vec::new(),
             session_id: None,
             blockers: Vec::new(),
+            findings_incremental: false,
         }
     }
 
@@ -67,6 +96,304 @@ impl Checkpoint {
     pub fn add_blocker(&mut self, blocker: impl Into<String>) {
         self.blockers.push(blocker.into());
     }
+
+    /// Parse, reject versions this build doesn't understand, and migrate
+    /// older versions to the current schema.
+    pub fn from_json(json: &str) -> Result<Self, CheckpointError> {
+        let checkpoint: Checkpoint = serde_json::from_str(json)
+            .map_err(|e| CheckpointError::Parse(e.to_string()))?;
+        checkpoint.check_version()?;
+        Ok(checkpoint.migrate())
+    }
+
+    /// Check that `tasks_snapshot` fits the checkpoint's stage. Tasks from
+    /// later stages are an error; a non-empty snapshot with no task in the
+    /// stage itself is returned as a warning.
+    pub fn check_stage_consistency(&self) -> Result<Vec<String>, CheckpointError> {
+        let mut past: Vec<String> = self.tasks_snapshot.iter()
+            .filter(|t| t.stage > self.stage)
+            .map(|t| t.id.clone())
+            .collect();
+        if !past.is_empty() {
+            past.sort();
+            return Err(CheckpointError::TasksPastStage { stage: self.stage, tasks: past });
+        }
+
+        let mut warnings = Vec::new();
+        if !self.tasks_snapshot.is_empty() && !self.tasks_snapshot.iter().any(|t| t.stage == self.stage) {
+            warnings.push(format!("Snapshot has no tasks in stage {}", self.stage.as_str()));
+        }
+        Ok(warnings)
+    }
+
+    pub fn check_version(&self) -> Result<(), CheckpointError> {
+        if self.schema_version > CHECKPOINT_SCHEMA_VERSION {
+            return Err(CheckpointError::UnsupportedVersion {
+                found: self.schema_version,
+                supported: CHECKPOINT_SCHEMA_VERSION,
+            });
+        }
+        Ok(())
+    }
+
+    /// Upgrade an older checkpoint to the current schema. Versions newer
+    /// than this build are returned unchanged; use `check_version` first.
+    pub fn migrate(mut self) -> Self {
+        // 0 -> 1: fields added before versioning (session_id, blockers,
+        // findings_incremental) already deserialize with defaults.
+        if self.schema_version == 0 {
+            self.schema_version = 1;
+        }
+        self
+    }
+}
+
+/// A section of a compiled checkpoint briefing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    Stage,
+    /// How long ago the checkpoint was created.
+    Age,
+    Session,
+    Decisions,
+    TasksSummary,
+    Blockers,
+    KeyFindings,
+}
+
+impl Section {
+    pub fn all() -> &'static [Section] {
+        &[
+            Section::Stage,
+            Section::Age,
+            Section::Session,
+            Section::Decisions,
+            Section::TasksSummary,
+            Section::Blockers,
+            Section::KeyFindings,
+        ]
+    }
+}
+
+/// One section of a compiled briefing, independent of presentation.
+/// Stage, Age and Session carry a single item, the value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BriefingSection {
+    pub section: Section,
+    pub heading: String,
+    pub items: Vec<String>,
+}
+
+impl BriefingSection {
+    pub fn new(section: Section, heading: impl Into<String>, items: Vec<String>) -> Self {
+        Self {
+            section,
+            heading: heading.into(),
+            items,
+        }
+    }
+
+    /// Markdown as produced by `CheckpointCompiler::compile`.
+    pub fn to_markdown(&self) -> String {
+        let value = self.items.first().map(String::as_str).unwrap_or("");
+        match self.section {
+            Section::Stage => format!("## {}: {}", self.heading, value),
+            Section::Age | Section::Session => format!("**{}:** {}", self.heading, value),
+            _ => {
+                let mut s = format!("## {}\n", self.heading);
+                for item in &self.items {
+                    s.push_str(&format!("- {}\n", item));
+                }
+                s
+            }
+        }
+    }
+}
+
+/// Order in which findings are listed under Key Findings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FindingOrder {
+    /// As stored in the checkpoint.
+    #[default]
+    Insertion,
+    /// Most recently recorded first.
+    NewestFirst,
+    /// Grouped by finding type, following `FindingType::all()`.
+    ByType,
+    /// Most severe first (critical, high, medium, low, other, unset),
+    /// then by summary and type.
+    BySeverity,
+}
+
+/// Controls what `CheckpointCompiler::compile_with_options` renders.
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    /// Maximum findings listed before collapsing into "... and N more".
+    /// `None` lists every finding. With per-type caps set, this is the
+    /// cap for each type without its own.
+    pub max_findings: Option<usize>,
+    pub include_sections: HashSet<Section>,
+    pub finding_order: FindingOrder,
+    /// Maximum findings listed per finding type.
+    pub type_caps: HashMap<FindingType, usize>,
+}
+
+impl CompileOptions {
+    pub fn with_max_findings(mut self, max: Option<usize>) -> Self {
+        self.max_findings = max;
+        self
+    }
+
+    pub fn with_sections(mut self, sections: &[Section]) -> Self {
+        self.include_sections = sections.iter().copied().collect();
+        self
+    }
+
+    pub fn without_section(mut self, section: Section) -> Self {
+        self.include_sections.remove(&section);
+        self
+    }
+
+    pub fn with_finding_order(mut self, order: FindingOrder) -> Self {
+        self.finding_order = order;
+        self
+    }
+
+    /// Cap findings of one type, e.g. `usize::MAX` for blockers so none
+    /// are hidden.
+    pub fn with_type_cap(mut self, finding_type: FindingType, cap: usize) -> Self {
+        self.type_caps.insert(finding_type, cap);
+        self
+    }
+
+    fn includes(&self, section: Section) -> bool {
+        self.include_sections.contains(&section)
+    }
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            max_findings: Some(5), // Limit to keep briefing concise
+            include_sections: Section::all().iter().copied().collect(),
+            finding_order: FindingOrder::Insertion,
+            type_caps: HashMap::new(),
+        }
+    }
+}
+
+/// Controls what `CheckpointCompiler::compile_redacted` scrubs.
+#[derive(Debug, Clone)]
+pub struct RedactOptions {
+    /// Omit the session id.
+    pub strip_session: bool,
+    /// Words made of this prefix followed only by digits, e.g. `worker-7`,
+    /// are treated as worker ids. Empty disables prefix detection.
+    pub worker_prefix: String,
+    /// Additional worker ids to anonymize, matched as whole words.
+    pub worker_ids: Vec<String>,
+}
+
+impl RedactOptions {
+    pub fn with_worker_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.worker_prefix = prefix.into();
+        self
+    }
+
+    pub fn with_worker_ids(mut self, ids: Vec<String>) -> Self {
+        self.worker_ids = ids;
+        self
+    }
+
+    fn is_worker_id(&self, word: &str) -> bool {
+        self.worker_ids.iter().any(|id| id == word)
+            || (!self.worker_prefix.is_empty()
+                && word.strip_prefix(self.worker_prefix.as_str())
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())))
+    }
+}
+
+impl Default for RedactOptions {
+    fn default() -> Self {
+        Self {
+            strip_session: true,
+            worker_prefix: "worker-".to_string(),
+            worker_ids: Vec::new(),
+        }
+    }
+}
+
+/// Replaces worker ids with `worker-A`, `worker-B`, ... in order of first
+/// appearance, so the same id always gets the same label.
+struct WorkerAnonymizer<'a> {
+    options: &'a RedactOptions,
+    labels: Vec<(String, String)>,
+}
+
+impl WorkerAnonymizer<'_> {
+    fn label_for(&mut self, id: &str) -> String {
+        if let Some((_, label)) = self.labels.iter().find(|(known, _)| known == id) {
+            return label.clone();
+        }
+        // Bijective base 26: A..Z, AA..AZ, ...
+        let mut n = self.labels.len() + 1;
+        let mut letters = Vec::new();
+        while n > 0 {
+            n -= 1;
+            letters.push((b'A' + (n % 26) as u8) as char);
+            n /= 26;
+        }
+        let label = format!("worker-{}", letters.iter().rev().collect::<String>());
+        self.labels.push((id.to_string(), label.clone()));
+        label
+    }
+
+    fn redact(&mut self, text: &str) -> String {
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(is_word_char) {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest.find(|c: char| !is_word_char(c)).unwrap_or(rest.len());
+            let word = &rest[..end];
+            if self.options.is_worker_id(word) {
+                out.push_str(&self.label_for(word));
+            } else {
+                out.push_str(word);
+            }
+            rest = &rest[end..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Last resort for budgeted compilation: drop trailing lines until `text`
+/// fits, then cut the remaining line to its longest prefix that does.
+fn truncate_to_fit(text: &str, fits: impl Fn(&str) -> bool) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+    while lines.len() > 1 && !fits(&lines.join("\n")) {
+        lines.pop();
+    }
+    let kept = lines.join("\n");
+    if fits(&kept) {
+        return kept;
+    }
+
+    // Binary search on the number of chars kept; the empty prefix fits
+    let ends: Vec<usize> = kept.char_indices().map(|(i, c)| i + c.len_utf8()).collect();
+    let prefix = |chars: usize| if chars == 0 { "" } else { &kept[..ends[chars - 1]] };
+    let (mut lo, mut hi) = (0, ends.len());
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if fits(prefix(mid)) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    prefix(lo).to_string()
 }
 
 /// Compiles a checkpoint into a concise markdown briefing (~500 tokens).
@@ -74,27 +401,98 @@ pub struct CheckpointCompiler;
 
 impl CheckpointCompiler {
     pub fn compile(checkpoint: &Checkpoint) -> String {
+        Self::compile_at(checkpoint, Self::now())
+    }
+
+    /// Compile as of `now` (Unix seconds), for reproducible output.
+    pub fn compile_at(checkpoint: &Checkpoint, now: u64) -> String {
+        Self::compile_with_options_at(checkpoint, &CompileOptions::default(), now)
+    }
+
+    pub fn compile_with_options(checkpoint: &Checkpoint, options: &CompileOptions) -> String {
+        Self::compile_with_options_at(checkpoint, options, Self::now())
+    }
+
+    pub fn compile_with_options_at(checkpoint: &Checkpoint, options: &CompileOptions, now: u64) -> String {
+        Self::compile_sections_with_options_at(checkpoint, options, now)
+            .iter()
+            .map(BriefingSection::to_markdown)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Compile for golden-file comparison: the output depends only on the
+    /// checkpoint. Findings are ordered `FindingOrder::BySeverity` and the
+    /// clock-relative age line is left out.
+    pub fn compile_stable(checkpoint: &Checkpoint) -> String {
+        let options = CompileOptions::default()
+            .without_section(Section::Age)
+            .with_finding_order(FindingOrder::BySeverity);
+        Self::compile_with_options_at(checkpoint, &options, 0)
+    }
+
+    /// Compile a briefing safe to share outside the mission: the session
+    /// id is dropped and worker ids in decisions, blockers and findings
+    /// are replaced with consistent anonymous labels.
+    pub fn compile_redacted(checkpoint: &Checkpoint, redact: &RedactOptions) -> String {
+        let mut redacted = checkpoint.clone();
+        if redact.strip_session {
+            redacted.session_id = None;
+        }
+
+        // Label in rendering order so worker-A is the first one a reader sees
+        let mut anonymizer = WorkerAnonymizer { options: redact, labels: Vec::new() };
+        for decision in &mut redacted.decisions {
+            *decision = anonymizer.redact(decision);
+        }
+        for blocker in &mut redacted.blockers {
+            *blocker = anonymizer.redact(blocker);
+        }
+        for finding in &mut redacted.findings_snapshot {
+            finding.summary = anonymizer.redact(&finding.summary);
+        }
+
+        Self::compile(&redacted)
+    }
+
+    /// The briefing content as structured sections, for renderers other
+    /// than markdown.
+    pub fn compile_sections(checkpoint: &Checkpoint) -> Vec<BriefingSection> {
+        Self::compile_sections_with_options_at(checkpoint, &CompileOptions::default(), Self::now())
+    }
+
+    pub fn compile_sections_with_options_at(
+        checkpoint: &Checkpoint,
+        options: &CompileOptions,
+        now: u64,
+    ) -> Vec<BriefingSection> {
         let mut sections = Vec::new();
 
         // Stage
-        sections.push(format!("## Stage: {}", checkpoint.stage.as_str()));
+        if options.includes(Section::Stage) {
+            sections.push(BriefingSection::new(Section::Stage, "Stage", vec![checkpoint.stage.as_str().to_string()]));
+        }
+
+        // Age (skipped when the creation time is unknown)
+        if options.includes(Section::Age) && checkpoint.created_at > 0 {
+            let age = Self::format_age(now.saturating_sub(checkpoint.created_at));
+            sections.push(BriefingSection::new(Section::Age, "Created", vec![age]));
+        }
 
         // Session
-        if let Some(ref session_id) = checkpoint.session_id {
-            sections.push(format!("**Session:** {}", session_id));
+        if options.includes(Section::Session) {
+            if let Some(ref session_id) = checkpoint.session_id {
+                sections.push(BriefingSection::new(Section::Session, "Session", vec![session_id.clone()]));
+            }
         }
 
         // Decisions
-        if !checkpoint.decisions.is_empty() {
-            let mut s = String::from("## Decisions\n");
-            for d in &checkpoint.decisions {
-                s.push_str(&format!("- {}\n", d));
-            }
-            sections.push(s);
+        if options.includes(Section::Decisions) && !checkpoint.decisions.is_empty() {
+            sections.push(BriefingSection::new(Section::Decisions, "Decisions", checkpoint.decisions.clone()));
         }
 
         // Tasks Summary
-        if !checkpoint.tasks_snapshot.is_empty() {
+        if options.includes(Section::TasksSummary) && !checkpoint.tasks_snapshot.is_empty() {
             let total = checkpoint.tasks_snapshot.len();
             let done = checkpoint.tasks_snapshot.iter()
                 .filter(|t| t.is_done())
@@ -104,43 +502,298 @@ impl CheckpointCompiler {
                 .count();
             let pending = total - done - blocked;
 
-            let mut s = format!("## Tasks Summary\n- Total: {}\n- Done: {}\n- Pending: {}\n", total, done, pending);
+            let mut items = vec![
+                format!("Total: {}", total),
+                format!("Done: {}", done),
+                format!("Pending: {}", pending),
+            ];
             if blocked > 0 {
-                s.push_str(&format!("- Blocked: {}\n", blocked));
+                items.push(format!("Blocked: {}", blocked));
             }
-            sections.push(s);
+            sections.push(BriefingSection::new(Section::TasksSummary, "Tasks Summary", items));
         }
 
         // Blockers
-        if !checkpoint.blockers.is_empty() {
-            let mut s = String::from("## Blockers\n");
-            for b in &checkpoint.blockers {
-                s.push_str(&format!("- {}\n", b));
-            }
-            sections.push(s);
+        if options.includes(Section::Blockers) && !checkpoint.blockers.is_empty() {
+            sections.push(BriefingSection::new(Section::Blockers, "Blockers", checkpoint.blockers.clone()));
         }
 
         // Key Findings
-        if !checkpoint.findings_snapshot.is_empty() {
-            let mut s = String::from("## Key Findings\n");
-            for (i, f) in checkpoint.findings_snapshot.iter().enumerate() {
-                if i >= 5 { break; } // Limit to keep briefing concise
+        if options.includes(Section::KeyFindings) && !checkpoint.findings_snapshot.is_empty() {
+            let findings = Self::order_findings(&checkpoint.findings_snapshot, options.finding_order);
+            let shown = Self::cap_findings(&findings, options);
+
+            let mut items: Vec<String> = shown.iter()
+                .map(|f| format!("[{}] {}", f.finding_type.as_str(), f.summary))
+                .collect();
+            if findings.len() > shown.len() {
+                items.push(format!("... and {} more", findings.len() - shown.len()));
+            }
+            sections.push(BriefingSection::new(Section::KeyFindings, "Key Findings", items));
+        }
+
+        sections
+    }
+
+    /// Token count of a compiled briefing under `counter`'s tokenizer. This
+    /// is the measure budgeted compilation enforces; prefer it to
+    /// character-based estimates, which drift badly on code-heavy text.
+    pub fn estimated_tokens(briefing: &str, counter: &TokenCounter) -> usize {
+        counter.count(briefing)
+    }
+
+    /// Compile a briefing with optional successor context, trimming
+    /// lower-priority content until it fits in `max_tokens` (0 = no limit).
+    ///
+    /// Findings are cut first, then tasks summary, decisions, and blockers.
+    /// Stage, session and successor context are kept; if they alone exceed
+    /// the limit, trailing lines are dropped and then the first line cut.
+    pub fn compile_within_budget(
+        checkpoint: &Checkpoint,
+        successor: Option<&SuccessorContext>,
+        max_tokens: usize,
+        counter: &TokenCounter,
+    ) -> String {
+        let fits = |text: &str| max_tokens == 0 || Self::estimated_tokens(text, counter) <= max_tokens;
+
+        let defaults = CompileOptions::default();
+        let attempts = [
+            defaults.clone(),
+            defaults.clone().with_max_findings(Some(2)),
+            defaults.clone().without_section(Section::KeyFindings),
+            defaults.clone()
+                .without_section(Section::KeyFindings)
+                .without_section(Section::TasksSummary),
+            defaults.clone()
+                .without_section(Section::KeyFindings)
+                .without_section(Section::TasksSummary)
+                .without_section(Section::Decisions),
+            defaults
+                .with_sections(&[Section::Stage, Section::Age, Section::Session]),
+        ];
+
+        let mut briefing = String::new();
+        for options in &attempts {
+            briefing = Self::compile_with_options(checkpoint, options);
+            if let Some(ctx) = successor {
+                briefing.push('\n');
+                briefing.push_str(&Self::render_successor(ctx));
+            }
+            if fits(&briefing) {
+                return briefing;
+            }
+        }
+
+        truncate_to_fit(&briefing, fits)
+    }
+
+    /// Render the full briefing a successor worker reads: the task, the
+    /// latest checkpoint, everything recorded in deltas since it, and the
+    /// relevant findings, trimmed to fit `max_tokens` (0 = no limit).
+    ///
+    /// Findings and modified files are cut first, then checkpoint detail;
+    /// the task header and delta decisions/questions are kept longest.
+    pub fn compile_briefing(inputs: &BriefingInputs, counter: &TokenCounter, max_tokens: usize) -> String {
+        let fits = |text: &str| max_tokens == 0 || Self::estimated_tokens(text, counter) <= max_tokens;
+
+        let checkpoint_defaults = CompileOptions::default().without_section(Section::KeyFindings);
+        let attempts = [
+            (Some(5), true, checkpoint_defaults.clone()),
+            (Some(2), false, checkpoint_defaults.clone()),
+            (Some(0), false, checkpoint_defaults.clone().without_section(Section::TasksSummary)),
+            (Some(0), false, checkpoint_defaults.with_sections(&[Section::Stage, Section::Age, Section::Session])),
+        ];
+
+        let now = Self::now();
+        let mut briefing = String::new();
+        for (max_findings, include_files, options) in &attempts {
+            briefing = Self::render_briefing(inputs, *max_findings, *include_files, options, now);
+            if fits(&briefing) {
+                return briefing;
+            }
+        }
+
+        truncate_to_fit(&briefing, fits)
+    }
+
+    fn render_briefing(
+        inputs: &BriefingInputs,
+        max_findings: Option<usize>,
+        include_files: bool,
+        checkpoint_options: &CompileOptions,
+        now: u64,
+    ) -> String {
+        let task = &inputs.task;
+        let mut sections = vec![format!(
+            "# Briefing: {}\n**Task:** {} ({}, zone {}, persona {})",
+            task.name, task.id, task.stage.as_str(), task.zone, task.persona
+        )];
+
+        if let Some(ref checkpoint) = inputs.checkpoint {
+            let compiled = Self::compile_with_options_at(checkpoint, checkpoint_options, now);
+            if !compiled.is_empty() {
+                sections.push(compiled);
+            }
+        }
+
+        // Deltas since the checkpoint, merged and de-duplicated
+        let mut decisions: Vec<&str> = Vec::new();
+        let mut files: Vec<&str> = Vec::new();
+        let mut questions: Vec<&str> = Vec::new();
+        for delta in &inputs.deltas {
+            for d in &delta.new_decisions {
+                if !decisions.contains(&d.as_str()) {
+                    decisions.push(d);
+                }
+            }
+            for f in &delta.modified_files {
+                if !files.contains(&f.as_str()) {
+                    files.push(f);
+                }
+            }
+            for q in &delta.open_questions {
+                if !questions.contains(&q.as_str()) {
+                    questions.push(q);
+                }
+            }
+        }
+
+        let mut since = String::new();
+        for d in &decisions {
+            since.push_str(&format!("- Decision: {}\n", d));
+        }
+        for q in &questions {
+            since.push_str(&format!("- Open question: {}\n", q));
+        }
+        if include_files && !files.is_empty() {
+            since.push_str(&format!("- Modified: {}\n", files.join(", ")));
+        }
+        if !since.is_empty() {
+            sections.push(format!("## Since Checkpoint\n{}", since));
+        }
+
+        // Relevant findings plus any recorded only in deltas
+        let mut findings: Vec<&Finding> = Vec::new();
+        let delta_findings = inputs.deltas.iter().flat_map(|d| &d.new_findings);
+        for f in inputs.relevant_findings.iter().chain(delta_findings) {
+            if !findings.iter().any(|seen| seen.same_as(f)) {
+                findings.push(f);
+            }
+        }
+
+        let limit = max_findings.unwrap_or(findings.len());
+        if limit > 0 && !findings.is_empty() {
+            let mut s = String::from("## Relevant Findings\n");
+            for f in findings.iter().take(limit) {
                 s.push_str(&format!("- [{}] {}\n", f.finding_type.as_str(), f.summary));
             }
-            if checkpoint.findings_snapshot.len() > 5 {
-                s.push_str(&format!("- ... and {} more\n", checkpoint.findings_snapshot.len() - 5));
+            if findings.len() > limit {
+                s.push_str(&format!("- ... and {} more\n", findings.len() - limit));
             }
             sections.push(s);
         }
 
         sections.join("\n")
     }
+
+    fn now() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    /// Humane relative age: "just now", "5m ago", "3h ago", "yesterday", "4d ago".
+    fn format_age(secs: u64) -> String {
+        const MINUTE: u64 = 60;
+        const HOUR: u64 = 60 * MINUTE;
+        const DAY: u64 = 24 * HOUR;
+
+        if secs < MINUTE {
+            "just now".to_string()
+        } else if secs < HOUR {
+            format!("{}m ago", secs / MINUTE)
+        } else if secs < DAY {
+            format!("{}h ago", secs / HOUR)
+        } else if secs < 2 * DAY {
+            "yesterday".to_string()
+        } else {
+            format!("{}d ago", secs / DAY)
+        }
+    }
+
+    fn render_successor(ctx: &SuccessorContext) -> String {
+        let mut s = String::from("## Context from Predecessor\n");
+        if let Some(ref approach) = ctx.recommended_approach {
+            s.push_str(&format!("**Recommended approach:** {}\n", approach));
+        }
+        for d in &ctx.key_decisions {
+            s.push_str(&format!("- Decision: {}\n", d));
+        }
+        for g in &ctx.gotchas {
+            s.push_str(&format!("- Gotcha: {}\n", g));
+        }
+        s
+    }
+
+    fn order_findings(findings: &[Finding], order: FindingOrder) -> Vec<&Finding> {
+        match order {
+            FindingOrder::Insertion => findings.iter().collect(),
+            FindingOrder::NewestFirst => findings.iter().rev().collect(),
+            FindingOrder::ByType => FindingType::all()
+                .iter()
+                .flat_map(|ft| findings.iter().filter(move |f| &f.finding_type == ft))
+                .collect(),
+            FindingOrder::BySeverity => {
+                let mut ordered: Vec<&Finding> = findings.iter().collect();
+                ordered.sort_by(|a, b| {
+                    Self::severity_rank(a).cmp(&Self::severity_rank(b))
+                        .then_with(|| a.summary.cmp(&b.summary))
+                        .then_with(|| a.finding_type.as_str().cmp(b.finding_type.as_str()))
+                });
+                ordered
+            }
+        }
+    }
+
+    /// The findings to list: the first `max_findings` overall, or with
+    /// per-type caps, the first few of each type.
+    fn cap_findings<'a>(findings: &[&'a Finding], options: &CompileOptions) -> Vec<&'a Finding> {
+        if options.type_caps.is_empty() {
+            let limit = options.max_findings.unwrap_or(findings.len());
+            return findings.iter().take(limit).copied().collect();
+        }
+
+        let mut counts: HashMap<&FindingType, usize> = HashMap::new();
+        findings.iter()
+            .copied()
+            .filter(|f| {
+                let cap = options.type_caps.get(&f.finding_type).copied()
+                    .or(options.max_findings)
+                    .unwrap_or(usize::MAX);
+                let count = counts.entry(&f.finding_type).or_insert(0);
+                *count += 1;
+                *count <= cap
+            })
+            .collect()
+    }
+
+    fn severity_rank(finding: &Finding) -> u8 {
+        match finding.severity.as_deref().map(str::to_lowercase).as_deref() {
+            Some("critical") => 0,
+            Some("high") => 1,
+            Some("medium") => 2,
+            Some("low") => 3,
+            Some(_) => 4,
+            None => 5,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::handoff::Finding;
+    use crate::handoff::{Finding, SuccessorContext};
 
     #[test]
     fn test_checkpoint_creation() {
@@ -153,6 +806,40 @@ mod tests {
         assert!(checkpoint.blockers.is_empty());
     }
 
+    #[test]
+    fn test_checkpoint_schema_version() {
+        let legacy = r#"{"id": "cp-1", "stage": "design", "created_at": 1, "tasks_snapshot": [],
+            "findings_snapshot": [], "decisions": []}"#;
+        let checkpoint = Checkpoint::from_json(legacy).unwrap();
+        assert_eq!(checkpoint.schema_version, CHECKPOINT_SCHEMA_VERSION);
+        assert_eq!(Checkpoint::new("cp-2", Stage::Design).schema_version, CHECKPOINT_SCHEMA_VERSION);
+
+        let future = legacy.replacen('{', r#"{"schema_version": 99, "#, 1);
+        assert!(matches!(
+            Checkpoint::from_json(&future),
+            Err(CheckpointError::UnsupportedVersion { found: 99, .. })
+        ));
+        assert!(matches!(Checkpoint::from_json("{}"), Err(CheckpointError::Parse(_))));
+    }
+
+    #[test]
+    fn test_check_stage_consistency() {
+        let task = |id: &str, stage| Task::new(id, "Task", stage, "backend", "developer");
+
+        let consistent = Checkpoint::new("cp-1", Stage::Design)
+            .with_tasks(vec![task("task-1", Stage::Planning), task("task-2", Stage::Design)]);
+        assert!(consistent.check_stage_consistency().unwrap().is_empty());
+        assert!(Checkpoint::new("cp-2", Stage::Design).check_stage_consistency().unwrap().is_empty());
+
+        let earlier_only = Checkpoint::new("cp-3", Stage::Design).with_tasks(vec![task("task-1", Stage::Planning)]);
+        assert_eq!(earlier_only.check_stage_consistency().unwrap(), vec!["Snapshot has no tasks in stage design"]);
+
+        let ahead = Checkpoint::new("cp-4", Stage::Design)
+            .with_tasks(vec![task("task-9", Stage::Release), task("task-2", Stage::Design), task("task-5", Stage::Verify)]);
+        let err = ahead.check_stage_consistency().unwrap_err();
+        assert_eq!(err.to_string(), "Snapshot has tasks past stage design: task-5, task-9");
+    }
+
     #[test]
     fn test_checkpoint_with_data() {
         let finding = Finding::decision("Chose REST over GraphQL");
@@ -195,8 +882,306 @@ mod tests {
             .with_blockers(vec!["Blocker 1".to_string()]);
 
         let briefing = CheckpointCompiler::compile(&checkpoint);
-        // Rough token estimate: ~4 chars per token
-        let estimated_tokens = briefing.len() / 4;
-        assert!(estimated_tokens < 500, "Briefing too long: ~{} tokens", estimated_tokens);
+        let tokens = CheckpointCompiler::estimated_tokens(&briefing, &TokenCounter::new());
+        assert!(tokens < 500, "Briefing too long: {} tokens", tokens);
+    }
+
+    fn checkpoint_with_findings(n: usize) -> Checkpoint {
+        let findings = (0..n)
+            .map(|i| Finding::discovery(format!("Finding {}", i)))
+            .collect();
+        Checkpoint::new("cp-5", Stage::Implement).with_findings(findings)
+    }
+
+    #[test]
+    fn test_compile_default_limits_findings() {
+        let briefing = CheckpointCompiler::compile(&checkpoint_with_findings(7));
+        assert!(briefing.contains("Finding 4"));
+        assert!(!briefing.contains("Finding 5"));
+        assert!(briefing.contains("... and 2 more"));
+    }
+
+    #[test]
+    fn test_compile_sections() {
+        let checkpoint = checkpoint_with_findings(7).with_decisions(vec!["Use REST".to_string()]);
+        let sections = CheckpointCompiler::compile_sections(&checkpoint);
+
+        assert_eq!(sections[0], BriefingSection::new(Section::Stage, "Stage", vec!["implement".to_string()]));
+        let decisions = sections.iter().find(|s| s.section == Section::Decisions).unwrap();
+        assert_eq!(decisions.items, vec!["Use REST".to_string()]);
+        let findings = sections.iter().find(|s| s.section == Section::KeyFindings).unwrap();
+        assert_eq!(findings.heading, "Key Findings");
+        assert_eq!(findings.items.len(), 6);
+        assert_eq!(findings.items[0], "[discovery] Finding 0");
+
+        let markdown: Vec<String> = sections.iter().map(BriefingSection::to_markdown).collect();
+        assert_eq!(markdown.join("\n"), CheckpointCompiler::compile(&checkpoint));
+    }
+
+    #[test]
+    fn test_compile_redacted() {
+        let checkpoint = Checkpoint::new("cp-1", Stage::Implement)
+            .with_session_id("sess-123")
+            .with_decisions(vec![
+                "worker-7 owns the API".to_string(),
+                "Size the worker-pool for worker-7x".to_string(),
+            ])
+            .with_findings(vec![
+                Finding::discovery("worker-12 found a race; worker-7 confirmed"),
+                Finding::concern("alice-bot flagged flaky CI"),
+            ]);
+
+        let briefing = CheckpointCompiler::compile_redacted(&checkpoint, &RedactOptions::default());
+        assert!(!briefing.contains("sess-123"));
+        assert!(briefing.contains("- worker-A owns the API"));
+        assert!(briefing.contains("worker-B found a race; worker-A confirmed"));
+        assert!(briefing.contains("alice-bot"));
+        assert!(briefing.contains("Size the worker-pool for worker-7x"));
+
+        let options = RedactOptions::default().with_worker_ids(vec!["alice-bot".to_string()]);
+        let briefing = CheckpointCompiler::compile_redacted(&checkpoint, &options);
+        assert!(briefing.contains("worker-C flagged flaky CI"));
+    }
+
+    #[test]
+    fn test_compile_with_unlimited_findings() {
+        let options = CompileOptions::default().with_max_findings(None);
+        let briefing = CheckpointCompiler::compile_with_options(&checkpoint_with_findings(7), &options);
+        assert!(briefing.contains("Finding 6"));
+        assert!(!briefing.contains("more"));
+    }
+
+    #[test]
+    fn test_compile_excludes_sections() {
+        let checkpoint = Checkpoint::new("cp-6", Stage::Implement)
+            .with_decisions(vec!["Use Rust for core".to_string()])
+            .with_blockers(vec!["CI pipeline failing".to_string()]);
+
+        let options = CompileOptions::default().without_section(Section::Blockers);
+        let briefing = CheckpointCompiler::compile_with_options(&checkpoint, &options);
+        assert!(briefing.contains("## Decisions"));
+        assert!(!briefing.contains("## Blockers"));
+
+        let options = CompileOptions::default().with_sections(&[Section::Blockers]);
+        let briefing = CheckpointCompiler::compile_with_options(&checkpoint, &options);
+        assert!(!briefing.contains("## Stage"));
+        assert!(briefing.contains("## Blockers"));
+    }
+
+    #[test]
+    fn test_compile_finding_order() {
+        let checkpoint = Checkpoint::new("cp-7", Stage::Implement).with_findings(vec![
+            Finding::concern("Concern A"),
+            Finding::discovery("Discovery B"),
+            Finding::blocker("Blocker C"),
+        ]);
+
+        let newest = CompileOptions::default().with_finding_order(FindingOrder::NewestFirst);
+        let briefing = CheckpointCompiler::compile_with_options(&checkpoint, &newest);
+        assert!(briefing.find("Blocker C").unwrap() < briefing.find("Concern A").unwrap());
+
+        let by_type = CompileOptions::default().with_finding_order(FindingOrder::ByType);
+        let briefing = CheckpointCompiler::compile_with_options(&checkpoint, &by_type);
+        let d = briefing.find("Discovery B").unwrap();
+        let b = briefing.find("Blocker C").unwrap();
+        let c = briefing.find("Concern A").unwrap();
+        assert!(d < b && b < c);
+    }
+
+    #[test]
+    fn test_compile_per_type_caps() {
+        let mut findings: Vec<Finding> = (0..6).map(|i| Finding::discovery(format!("Discovery {}", i))).collect();
+        findings.extend((0..7).map(|i| Finding::blocker(format!("Blocker {}", i))));
+        findings.push(Finding::concern("Concern 0"));
+        let checkpoint = Checkpoint::new("cp-7", Stage::Implement).with_findings(findings);
+
+        let options = CompileOptions::default()
+            .with_finding_order(FindingOrder::ByType)
+            .with_max_findings(Some(1))
+            .with_type_cap(FindingType::Blocker, usize::MAX)
+            .with_type_cap(FindingType::Discovery, 3);
+        let briefing = CheckpointCompiler::compile_with_options(&checkpoint, &options);
+
+        assert!(briefing.contains("Discovery 2") && !briefing.contains("Discovery 3"));
+        assert!((0..7).all(|i| briefing.contains(&format!("Blocker {}", i))));
+        assert!(briefing.contains("Concern 0"));
+        assert!(briefing.contains("... and 3 more"));
+    }
+
+    #[test]
+    fn test_compile_stable_orders_by_severity() {
+        let findings = vec![
+            Finding::discovery("Zebra"),
+            Finding::concern("Slow query").with_severity("low"),
+            Finding::blocker("No creds").with_severity("Critical"),
+            Finding::discovery("Apple"),
+            Finding::concern("Leaky cache").with_severity("high"),
+        ];
+        let mut reversed = findings.clone();
+        reversed.reverse();
+
+        let mut checkpoint = Checkpoint::new("cp-7", Stage::Implement).with_findings(findings);
+        checkpoint.created_at = 1;
+        let mut shuffled = Checkpoint::new("cp-7", Stage::Implement).with_findings(reversed);
+        shuffled.created_at = 1;
+
+        let briefing = CheckpointCompiler::compile_stable(&checkpoint);
+        assert_eq!(briefing, CheckpointCompiler::compile_stable(&shuffled));
+        assert!(!briefing.contains("**Created:**"));
+
+        let order: Vec<usize> = ["No creds", "Leaky cache", "Slow query", "Apple", "Zebra"].iter()
+            .map(|s| briefing.find(s).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_compile_within_budget_includes_successor_context() {
+        let counter = TokenCounter::approximate();
+        let checkpoint = Checkpoint::new("cp-8", Stage::Implement)
+            .with_decisions(vec!["Use Rust for core".to_string()]);
+        let successor = SuccessorContext::new()
+            .with_decision("Keep FFI JSON-based")
+            .with_gotcha("tiktoken is slow to init")
+            .with_approach("Extend the compiler");
+
+        let briefing = CheckpointCompiler::compile_within_budget(&checkpoint, Some(&successor), 0, &counter);
+        assert!(briefing.contains("## Decisions"));
+        assert!(briefing.contains("## Context from Predecessor"));
+        assert!(briefing.contains("Gotcha: tiktoken is slow to init"));
+        assert!(briefing.contains("**Recommended approach:** Extend the compiler"));
+    }
+
+    #[test]
+    fn test_compile_within_budget_trims_to_fit() {
+        let counter = TokenCounter::approximate();
+        let findings = (0..10)
+            .map(|i| Finding::discovery(format!("A fairly long finding summary number {}", i)))
+            .collect();
+        let checkpoint = Checkpoint::new("cp-9", Stage::Implement)
+            .with_findings(findings)
+            .with_decisions(vec!["Use Rust for core".to_string()]);
+        let successor = SuccessorContext::new().with_gotcha("Watch the FFI boundary");
+
+        let full = CheckpointCompiler::compile_within_budget(&checkpoint, Some(&successor), 0, &counter);
+        let max_tokens = counter.count(&full) / 2;
+
+        let trimmed = CheckpointCompiler::compile_within_budget(&checkpoint, Some(&successor), max_tokens, &counter);
+        assert!(counter.count(&trimmed) <= max_tokens);
+        assert!(trimmed.contains("## Stage: implement"));
+        assert!(trimmed.contains("Watch the FFI boundary"));
+    }
+
+    #[test]
+    fn test_compile_within_budget_truncates_lines_as_last_resort() {
+        let counter = TokenCounter::approximate();
+        let checkpoint = Checkpoint::new("cp-10", Stage::Design);
+        let successor = SuccessorContext::new().with_gotcha("x".repeat(400));
+
+        let briefing = CheckpointCompiler::compile_within_budget(&checkpoint, Some(&successor), 20, &counter);
+        assert!(counter.count(&briefing) <= 20);
+        assert!(briefing.starts_with("## Stage: design"));
+
+        // Even the first line is cut when it alone is over budget
+        let briefing = CheckpointCompiler::compile_within_budget(&checkpoint, Some(&successor), 2, &counter);
+        assert!(counter.count(&briefing) <= 2);
+        assert!(briefing.starts_with("## S"));
+        assert!(!briefing.contains('\n'));
+    }
+
+    #[test]
+    fn test_compile_at_renders_age() {
+        let mut checkpoint = Checkpoint::new("cp-11", Stage::Design);
+        checkpoint.created_at = 1_000_000;
+
+        let cases = [
+            (1_000_030, "just now"),
+            (1_000_000 + 3 * 60, "3m ago"),
+            (1_000_000 + 2 * 3600 + 59, "2h ago"),
+            (1_000_000 + 30 * 3600, "yesterday"),
+            (1_000_000 + 5 * 86400, "5d ago"),
+            (999_000, "just now"), // clock skew
+        ];
+        for (now, expected) in cases {
+            let briefing = CheckpointCompiler::compile_at(&checkpoint, now);
+            assert!(briefing.contains(&format!("**Created:** {}", expected)), "{}: {}", now, briefing);
+        }
+    }
+
+    #[test]
+    fn test_compile_at_is_reproducible() {
+        let checkpoint = Checkpoint::new("cp-12", Stage::Design)
+            .with_decisions(vec!["Use REST".to_string()]);
+        let now = checkpoint.created_at + 7200;
+        assert_eq!(
+            CheckpointCompiler::compile_at(&checkpoint, now),
+            CheckpointCompiler::compile_at(&checkpoint, now)
+        );
+    }
+
+    #[test]
+    fn test_compile_skips_age_without_timestamp() {
+        let mut checkpoint = Checkpoint::new("cp-13", Stage::Design);
+        checkpoint.created_at = 0;
+        assert!(!CheckpointCompiler::compile(&checkpoint).contains("Created"));
+    }
+
+    fn briefing_inputs() -> BriefingInputs {
+        let checkpoint = Checkpoint::new("cp-20", Stage::Design)
+            .with_decisions(vec!["Use REST".to_string()])
+            .with_findings(vec![Finding::new(FindingType::Discovery, "Checkpoint-only finding")]);
+        let delta = crate::delta::Delta::new("cp-20")
+            .with_decisions(vec!["Paginate with cursors".to_string()])
+            .with_questions(vec!["Rate limits?".to_string()])
+            .with_files(vec!["api.rs".to_string()])
+            .with_findings(vec![
+                Finding::new(FindingType::Blocker, "Auth token expiry"),
+                Finding::new(FindingType::Discovery, "Delta-only finding"),
+            ]);
+
+        BriefingInputs {
+            task: Task::new("task-1", "Build API", Stage::Design, "backend", "architect"),
+            checkpoint: Some(checkpoint),
+            deltas: vec![delta.clone(), delta],
+            relevant_findings: vec![Finding::new(FindingType::Blocker, "Auth token expiry")],
+        }
+    }
+
+    #[test]
+    fn test_compile_briefing_merges_checkpoint_and_deltas() {
+        let counter = TokenCounter::approximate();
+        let briefing = CheckpointCompiler::compile_briefing(&briefing_inputs(), &counter, 0);
+
+        assert!(briefing.starts_with("# Briefing: Build API"));
+        assert!(briefing.contains("## Stage: design"));
+        assert!(briefing.contains("- Use REST"));
+        assert!(briefing.contains("- Decision: Paginate with cursors"));
+        assert!(briefing.contains("- Open question: Rate limits?"));
+        assert!(briefing.contains("- Modified: api.rs\n"));
+        assert_eq!(briefing.matches("Auth token expiry").count(), 1);
+        assert!(briefing.contains("Delta-only finding"));
+        // Checkpoint findings are superseded by the relevant findings list
+        assert!(!briefing.contains("Checkpoint-only finding"));
+    }
+
+    #[test]
+    fn test_compile_briefing_trims_to_budget() {
+        let counter = TokenCounter::approximate();
+        let inputs = briefing_inputs();
+        let full = CheckpointCompiler::compile_briefing(&inputs, &counter, 0);
+
+        let budget = counter.count(&full) - 10;
+        let trimmed = CheckpointCompiler::compile_briefing(&inputs, &counter, budget);
+        assert!(counter.count(&trimmed) <= budget);
+        assert!(trimmed.contains("Paginate with cursors"));
+        assert!(!trimmed.contains("api.rs"));
+
+        let tiny = CheckpointCompiler::compile_briefing(&inputs, &counter, 8);
+        assert!(counter.count(&tiny) <= 8);
+        assert!(tiny.starts_with("# Briefing"));
+
+        let tinier = CheckpointCompiler::compile_briefing(&inputs, &counter, 1);
+        assert!(counter.count(&tinier) <= 1);
+        assert!(tinier.starts_with("# B") && !tinier.contains('\n'));
     }
 }
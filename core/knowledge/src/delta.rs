@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
-use crate::handoff::Finding;
+use crate::checkpoint::Checkpoint;
+use crate::handoff::{Finding, Handoff};
 
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Delta {
     pub from_checkpoint: String,
@@ -28,6 +30,31 @@ impl Delta {
         }
     }
 
+    /// Build a delta from a worker's handoff: findings, artifacts, open
+    /// questions, and successor key decisions carry over directly.
+    pub fn from_handoff(from_checkpoint: &str, handoff: &Handoff) -> Self {
+        let decisions = handoff
+            .context_for_successor
+            .as_ref()
+            .map(|ctx| ctx.key_decisions.clone())
+            .unwrap_or_default();
+
+        // Findings from a handoff belong to its task unless already linked
+        let findings = handoff.findings.iter()
+            .cloned()
+            .map(|f| match f.task_id {
+                Some(_) => f,
+                None => f.with_task(&handoff.task_id),
+            })
+            .collect();
+
+        Self::new(from_checkpoint)
+            .with_findings(findings)
+            .with_files(handoff.artifacts.clone())
+            .with_decisions(decisions)
+            .with_questions(handoff.open_questions.clone())
+    }
+
     pub fn with_findings(mut self, findings: Vec<Finding>) -> Self {
         self.new_findings = findings;
         self
@@ -70,12 +97,32 @@ impl Delta {
             && self.new_decisions.is_empty()
             && self.open_questions.is_empty()
     }
+
+    /// Copy of this delta without findings and decisions `checkpoint`
+    /// already holds.
+    pub fn prune_against(&self, checkpoint: &Checkpoint) -> Delta {
+        self.clone().prune_against_parts(&checkpoint.findings_snapshot, &checkpoint.decisions)
+    }
+
+    /// `prune_against` for known findings and decisions that aren't held
+    /// in a `Checkpoint`, consuming the delta instead of copying it.
+    pub fn prune_against_parts(mut self, findings: &[Finding], decisions: &[String]) -> Delta {
+        self.new_findings.retain(|f| !findings.iter().any(|k| k.same_as(f)));
+        self.new_decisions.retain(|d| !decisions.contains(d));
+        self
+    }
+
+    /// Adds nothing beyond what `checkpoint` already holds, even if not
+    /// `is_empty`.
+    pub fn is_noop(&self, checkpoint: &Checkpoint) -> bool {
+        self.prune_against(checkpoint).is_empty()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::handoff::Finding;
+    use crate::handoff::{Finding, Handoff, SuccessorContext};
 
     #[test]
     fn test_delta_creation() {
@@ -108,4 +155,47 @@ mod tests {
         assert_eq!(delta.modified_files.len(), 1);
         assert_eq!(delta.open_questions.len(), 1);
     }
+
+    #[test]
+    fn test_delta_prune_against_checkpoint() {
+        let checkpoint = Checkpoint::new("cp-1", workflow::Stage::Implement)
+            .with_findings(vec![Finding::discovery("Found cache layer")])
+            .with_decisions(vec!["Use LRU".to_string()]);
+
+        let delta = Delta::new("cp-1")
+            .with_findings(vec![Finding::discovery("Found cache layer")])
+            .with_decisions(vec!["Use LRU".to_string()]);
+        assert!(!delta.is_empty());
+        assert!(delta.is_noop(&checkpoint));
+
+        let delta = delta.with_files(vec!["src/cache.rs".to_string()]);
+        let pruned = delta.prune_against(&checkpoint);
+        assert!(pruned.new_findings.is_empty());
+        assert!(pruned.new_decisions.is_empty());
+        assert_eq!(pruned.modified_files, vec!["src/cache.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_delta_from_handoff() {
+        let handoff = Handoff::complete("task-1", "worker-1")
+            .with_finding(Finding::discovery("Found cache layer"))
+            .with_artifact("src/cache.rs")
+            .with_question("Evict on write?")
+            .with_successor_context(SuccessorContext::new().with_decision("Use LRU"));
+
+        let delta = Delta::from_handoff("cp-1", &handoff);
+        assert_eq!(delta.from_checkpoint, "cp-1");
+        assert_eq!(delta.new_findings.len(), 1);
+        assert_eq!(delta.new_findings[0].task_id, Some("task-1".to_string()));
+        assert_eq!(delta.modified_files, vec!["src/cache.rs".to_string()]);
+        assert_eq!(delta.open_questions, vec!["Evict on write?".to_string()]);
+        assert_eq!(delta.new_decisions, vec!["Use LRU".to_string()]);
+    }
+
+    #[test]
+    fn test_delta_from_handoff_without_successor_context() {
+        let handoff = Handoff::partial("task-1", "worker-1");
+        let delta = Delta::from_handoff("cp-1", &handoff);
+        assert!(delta.is_empty());
+    }
 }
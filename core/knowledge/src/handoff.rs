@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use workflow::BlockReason;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum FindingType {
     Discovery,
@@ -10,6 +15,15 @@ pub enum FindingType {
 }
 
 impl FindingType {
+    pub fn all() -> &'static [FindingType] {
+        &[
+            FindingType::Discovery,
+            FindingType::Blocker,
+            FindingType::Decision,
+            FindingType::Concern,
+        ]
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             FindingType::Discovery => "discovery",
@@ -20,21 +34,31 @@ impl FindingType {
     }
 }
 
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
     pub finding_type: FindingType,
     pub summary: String,
     pub details_path: Option<String>,
     pub severity: Option<String>,
+    /// Task that produced this finding, if known.
+    #[serde(default)]
+    pub task_id: Option<String>,
 }
 
 impl Finding {
+    /// Same type and summary; task, details and severity are ignored.
+    pub fn same_as(&self, other: &Finding) -> bool {
+        self.finding_type == other.finding_type && self.summary == other.summary
+    }
+
     pub fn new(finding_type: FindingType, summary: impl Into<String>) -> Self {
         Self {
             finding_type,
             summary: summary.into(),
             details_path: None,
             severity: None,
+            task_id: None,
         }
     }
 
@@ -48,6 +72,11 @@ impl Finding {
         self
     }
 
+    pub fn with_task(mut self, task_id: impl Into<String>) -> Self {
+        self.task_id = Some(task_id.into());
+        self
+    }
+
     pub fn discovery(summary: impl Into<String>) -> Self {
         Self::new(FindingType::Discovery, summary)
     }
@@ -65,14 +94,16 @@ impl Finding {
     }
 }
 
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum HandoffStatus {
     Complete,
-    Blocked(String),
+    Blocked(BlockReason),
     Partial,
 }
 
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuccessorContext {
     pub key_decisions: Vec<String>,
@@ -103,6 +134,13 @@ impl SuccessorContext {
         self.recommended_approach = Some(approach.into());
         self
     }
+
+    /// True when there is nothing to pass on to the successor.
+    pub fn is_empty(&self) -> bool {
+        self.key_decisions.is_empty()
+            && self.gotchas.is_empty()
+            && self.recommended_approach.as_deref().is_none_or(|a| a.trim().is_empty())
+    }
 }
 
 impl Default for SuccessorContext {
@@ -111,6 +149,11 @@ impl Default for SuccessorContext {
     }
 }
 
+/// Version tag hashed ahead of the content by `Handoff::content_hash`.
+/// Bump it whenever the hashed projection changes.
+const HASH_FORMAT: &str = "mc-handoff-hash/v1";
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Handoff {
     pub task_id: String,
@@ -121,6 +164,9 @@ pub struct Handoff {
     pub open_questions: Vec<String>,
     pub context_for_successor: Option<SuccessorContext>,
     pub timestamp: u64,
+    /// `content_hash` recorded by `with_hash`, for detecting later edits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
 }
 
 impl Handoff {
@@ -139,6 +185,7 @@ impl Handoff {
             open_questions: Vec::new(),
             context_for_successor: None,
             timestamp: now,
+            hash: None,
         }
     }
 
@@ -146,7 +193,7 @@ impl Handoff {
         Self::new(task_id, worker_id, HandoffStatus::Complete)
     }
 
-    pub fn blocked(task_id: impl Into<String>, worker_id: impl Into<String>, reason: impl Into<String>) -> Self {
+    pub fn blocked(task_id: impl Into<String>, worker_id: impl Into<String>, reason: impl Into<BlockReason>) -> Self {
         Self::new(task_id, worker_id, HandoffStatus::Blocked(reason.into()))
     }
 
@@ -173,12 +220,243 @@ impl Handoff {
         self.context_for_successor = Some(context);
         self
     }
+
+    /// Record the current `content_hash` in `hash`. Call it last: any edit
+    /// made afterwards makes `verify_hash` fail.
+    pub fn with_hash(mut self) -> Self {
+        self.hash = Some(self.content_hash());
+        self
+    }
+
+    /// Hex SHA-256 over a fixed projection of the handoff's content.
+    ///
+    /// Only the fields listed in `HASH_FORMAT` are hashed, one `name=value`
+    /// line each with values JSON-quoted, and `None` values are skipped.
+    /// Adding a defaulted field to `Handoff` or `Finding` therefore leaves
+    /// existing hashes valid; changing what is hashed means bumping the
+    /// format version.
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(HASH_FORMAT.as_bytes());
+        hasher.update(b"\n");
+        let mut field = |name: &str, value: &str| {
+            hasher.update(name.as_bytes());
+            hasher.update(b"=");
+            hasher.update(Value::String(value.to_string()).to_string().as_bytes());
+            hasher.update(b"\n");
+        };
+
+        field("task_id", &self.task_id);
+        field("worker_id", &self.worker_id);
+        match &self.status {
+            HandoffStatus::Complete => field("status", "complete"),
+            HandoffStatus::Partial => field("status", "partial"),
+            HandoffStatus::Blocked(reason) => {
+                field("status", "blocked");
+                field("status.category", reason.category.as_str());
+                field("status.detail", &reason.detail);
+            }
+        }
+        for finding in &self.findings {
+            field("finding.type", finding.finding_type.as_str());
+            field("finding.summary", &finding.summary);
+            if let Some(path) = &finding.details_path {
+                field("finding.details_path", path);
+            }
+            if let Some(severity) = &finding.severity {
+                field("finding.severity", severity);
+            }
+        }
+        for artifact in &self.artifacts {
+            field("artifact", artifact);
+        }
+        for question in &self.open_questions {
+            field("open_question", question);
+        }
+        if let Some(context) = &self.context_for_successor {
+            field("context", "");
+            for decision in &context.key_decisions {
+                field("context.key_decision", decision);
+            }
+            for gotcha in &context.gotchas {
+                field("context.gotcha", gotcha);
+            }
+            if let Some(approach) = &context.recommended_approach {
+                field("context.recommended_approach", approach);
+            }
+        }
+        field("timestamp", &self.timestamp.to_string());
+
+        hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// True when the handoff's content still hashes to `expected`.
+    pub fn verify_hash(&self, expected: &str) -> bool {
+        self.content_hash().eq_ignore_ascii_case(expected)
+    }
+}
+
+impl Handoff {
+    /// Parse a handoff, salvaging whatever fields are well-formed.
+    ///
+    /// Each malformed or missing field is reported individually and replaced
+    /// by a default (empty string, empty list, `Partial` status, zero
+    /// timestamp). Returns `None` only if the input is not a JSON object.
+    pub fn parse_lenient(json: &str) -> (Option<Handoff>, Vec<String>) {
+        let mut diagnostics = Vec::new();
+
+        let value: Value = match serde_json::from_str(json) {
+            Ok(v) => v,
+            Err(e) => return (None, vec![format!("Invalid JSON: {}", e)]),
+        };
+        let obj = match value.as_object() {
+            Some(o) => o,
+            None => return (None, vec![format!("Expected a JSON object, got {}", value_kind(&value))]),
+        };
+
+        let task_id = lenient_field(obj, "task_id", true, &mut diagnostics).unwrap_or_default();
+        let worker_id = lenient_field(obj, "worker_id", true, &mut diagnostics).unwrap_or_default();
+        let status = lenient_field(obj, "status", true, &mut diagnostics).unwrap_or(HandoffStatus::Partial);
+        let findings = lenient_list(obj, "findings", true, &mut diagnostics);
+        let artifacts = lenient_list(obj, "artifacts", true, &mut diagnostics);
+        let open_questions = lenient_list(obj, "open_questions", true, &mut diagnostics);
+        let context_for_successor = lenient_field(obj, "context_for_successor", false, &mut diagnostics);
+        let timestamp = lenient_field(obj, "timestamp", true, &mut diagnostics).unwrap_or(0);
+        let hash = lenient_field(obj, "hash", false, &mut diagnostics);
+
+        let handoff = Handoff {
+            task_id,
+            worker_id,
+            status,
+            findings,
+            artifacts,
+            open_questions,
+            context_for_successor,
+            timestamp,
+            hash,
+        };
+
+        (Some(handoff), diagnostics)
+    }
+}
+
+fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Deserialize a single field, recording a diagnostic if it is malformed
+/// (or missing, when `required`). Explicit `null` counts as absent.
+fn lenient_field<T: DeserializeOwned>(
+    obj: &Map<String, Value>,
+    field: &str,
+    required: bool,
+    diagnostics: &mut Vec<String>,
+) -> Option<T> {
+    match obj.get(field) {
+        None | Some(Value::Null) => {
+            if required {
+                diagnostics.push(format!("{}: missing", field));
+            }
+            None
+        }
+        Some(v) => match serde_json::from_value(v.clone()) {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                diagnostics.push(format!("{}: {} (got {})", field, e, value_kind(v)));
+                None
+            }
+        },
+    }
+}
+
+/// Deserialize a list field element by element, keeping the valid entries.
+/// A missing list is reported like a missing `required` field.
+fn lenient_list<T: DeserializeOwned>(
+    obj: &Map<String, Value>,
+    field: &str,
+    required: bool,
+    diagnostics: &mut Vec<String>,
+) -> Vec<T> {
+    match obj.get(field) {
+        None | Some(Value::Null) => {
+            if required {
+                diagnostics.push(format!("{}: missing", field));
+            }
+            Vec::new()
+        }
+        Some(Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| match serde_json::from_value(item.clone()) {
+                Ok(parsed) => Some(parsed),
+                Err(e) => {
+                    diagnostics.push(format!("{}[{}]: {}", field, i, e));
+                    None
+                }
+            })
+            .collect(),
+        Some(other) => {
+            diagnostics.push(format!("{}: expected array (got {})", field, value_kind(other)));
+            Vec::new()
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_content_hash_detects_edits() {
+        let handoff = Handoff::complete("task-1", "worker-1")
+            .with_finding(Finding::discovery("Found auth module"))
+            .with_artifact("src/auth.rs")
+            .with_hash();
+        let stored = handoff.hash.clone().unwrap();
+        assert_eq!(stored.len(), 64);
+        assert!(handoff.verify_hash(&stored));
+
+        // The stored hash isn't part of the content, and survives a round trip
+        let restored: Handoff = serde_json::from_str(&serde_json::to_string(&handoff).unwrap()).unwrap();
+        assert_eq!(restored.content_hash(), stored);
+
+        let mut edited = restored;
+        edited.artifacts.push("src/backdoor.rs".to_string());
+        assert!(!edited.verify_hash(&stored));
+    }
+
+    #[test]
+    fn test_content_hash_survives_added_fields() {
+        // Written before findings had a `task_id`; the hash must not move
+        // now that the field exists and defaults to `None`.
+        let archived = r#"{
+            "task_id": "task-1",
+            "worker_id": "worker-1",
+            "status": {"blocked": {"category": "needs_human", "detail": "Pick a DB"}},
+            "findings": [{"finding_type": "decision", "summary": "Use Postgres", "details_path": null, "severity": "high"}],
+            "artifacts": ["schema.sql"],
+            "open_questions": [],
+            "context_for_successor": null,
+            "timestamp": 1700000000
+        }"#;
+        let handoff: Handoff = serde_json::from_str(archived).unwrap();
+        let hash = handoff.content_hash();
+        // Pinned: a change here means archived hashes stop verifying.
+        assert_eq!(hash, "73a99d19a19ebebb3ab7e442e6094867bc919b2a6428fdeb995f01dd06213302");
+        assert_eq!(hash, handoff.clone().with_hash().hash.unwrap());
+
+        let mut linked = handoff;
+        linked.findings[0].task_id = Some("task-1".to_string());
+        assert!(linked.verify_hash(&hash));
+    }
+
     #[test]
     fn test_finding_creation() {
         let finding = Finding::discovery("Found existing auth implementation")
@@ -190,6 +468,15 @@ mod tests {
         assert!(finding.details_path.is_some());
     }
 
+    #[test]
+    fn test_finding_task_link() {
+        let finding = Finding::discovery("Found cache").with_task("task-1");
+        assert_eq!(finding.task_id, Some("task-1".to_string()));
+
+        let legacy: Finding = serde_json::from_str(r#"{"finding_type":"concern","summary":"Old","details_path":null,"severity":null}"#).unwrap();
+        assert!(legacy.task_id.is_none());
+    }
+
     #[test]
     fn test_handoff_creation() {
         let handoff = Handoff::complete("task-1", "worker-1")
@@ -212,4 +499,77 @@ mod tests {
         assert!(json.contains("blocked"));
         assert!(json.contains("Waiting for API docs"));
     }
+
+    #[test]
+    fn test_handoff_blocked_reason_formats() {
+        use workflow::BlockCategory;
+
+        let legacy = r#"{"task_id":"t","worker_id":"w","status":{"blocked":"No creds"},
+            "findings":[],"artifacts":[],"open_questions":[],"context_for_successor":null,"timestamp":1}"#;
+        let handoff: Handoff = serde_json::from_str(legacy).unwrap();
+        assert_eq!(handoff.status, HandoffStatus::Blocked(BlockReason::other("No creds")));
+
+        let handoff = Handoff::blocked("t", "w", BlockReason::new(BlockCategory::ExternalDependency, "Vendor API down"));
+        let json = serde_json::to_string(&handoff).unwrap();
+        assert!(json.contains("external_dependency"));
+    }
+
+    #[test]
+    fn test_parse_lenient_valid() {
+        let json = serde_json::to_string(&Handoff::complete("task-1", "worker-1")).unwrap();
+        let (handoff, diagnostics) = Handoff::parse_lenient(&json);
+        assert!(diagnostics.is_empty());
+        assert_eq!(handoff.unwrap().task_id, "task-1");
+    }
+
+    #[test]
+    fn test_parse_lenient_reports_bad_fields() {
+        let json = r#"{
+            "task_id": "task-1",
+            "worker_id": 42,
+            "status": "complete",
+            "findings": [
+                {"finding_type": "discovery", "summary": "Good one"},
+                {"finding_type": "unknown", "summary": "Bad type"}
+            ],
+            "artifacts": "src/main.rs",
+            "timestamp": "yesterday"
+        }"#;
+
+        let (handoff, diagnostics) = Handoff::parse_lenient(json);
+        let handoff = handoff.unwrap();
+        assert_eq!(handoff.task_id, "task-1");
+        assert_eq!(handoff.worker_id, "");
+        assert_eq!(handoff.status, HandoffStatus::Complete);
+        assert_eq!(handoff.findings.len(), 1);
+        assert!(handoff.artifacts.is_empty());
+        assert_eq!(handoff.timestamp, 0);
+
+        assert!(diagnostics.iter().any(|d| d.starts_with("worker_id:")));
+        assert!(diagnostics.iter().any(|d| d.starts_with("findings[1]:")));
+        assert!(diagnostics.iter().any(|d| d.starts_with("artifacts:")));
+        assert!(diagnostics.iter().any(|d| d.starts_with("timestamp:") && d.contains("string")));
+        assert!(diagnostics.iter().any(|d| d == "open_questions: missing"));
+        assert_eq!(diagnostics.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_lenient_missing_status_defaults_to_partial() {
+        let (handoff, diagnostics) = Handoff::parse_lenient(
+            r#"{"task_id": "t", "worker_id": "w", "findings": [], "artifacts": [], "open_questions": [], "timestamp": 1}"#,
+        );
+        assert_eq!(handoff.unwrap().status, HandoffStatus::Partial);
+        assert_eq!(diagnostics, vec!["status: missing".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_lenient_rejects_non_object() {
+        let (handoff, diagnostics) = Handoff::parse_lenient("[1, 2]");
+        assert!(handoff.is_none());
+        assert_eq!(diagnostics.len(), 1);
+
+        let (handoff, diagnostics) = Handoff::parse_lenient("{not json");
+        assert!(handoff.is_none());
+        assert!(diagnostics[0].starts_with("Invalid JSON"));
+    }
 }
@@ -5,9 +5,9 @@ pub mod checkpoint;
 mod delta;
 mod manager;
 
-pub use tokens::TokenCounter;
-pub use budget::{TokenBudget, BudgetStatus};
+pub use tokens::{TokenCounter, TokenError, ApproximateCounter};
+pub use budget::{BudgetSnapshot, TokenBudget, BudgetStatus, BudgetError};
 pub use handoff::{Handoff, HandoffStatus, Finding, FindingType, SuccessorContext};
-pub use checkpoint::Checkpoint;
+pub use checkpoint::{BriefingSection, Checkpoint, CheckpointError, CHECKPOINT_SCHEMA_VERSION};
 pub use delta::Delta;
-pub use manager::{KnowledgeManager, BriefingInputs, ValidationError};
+pub use manager::{KnowledgeManager, BriefingInputs, StageSummary, ValidationError, ValidationReport};
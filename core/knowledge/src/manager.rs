@@ -1,11 +1,12 @@
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use workflow::{Stage, Task};
+use workflow::{Stage, Task, TransitionOutcome};
 
 use crate::tokens::TokenCounter;
-use crate::budget::{TokenBudget, BudgetStatus};
-use crate::handoff::{Handoff, Finding};
-use crate::checkpoint::Checkpoint;
+use crate::budget::{BudgetSnapshot, TokenBudget, BudgetStatus};
+use crate::handoff::{Handoff, Finding, FindingType};
+use crate::checkpoint::{Checkpoint, CheckpointCompiler};
 use crate::delta::Delta;
 
 #[derive(Debug, Error)]
@@ -21,6 +22,36 @@ pub enum ValidationError {
 
     #[error("Blocked status requires blocked_reason")]
     MissingBlockedReason,
+
+    #[error("Partial status requires open_questions or context_for_successor")]
+    IncompletePartialHandoff,
+}
+
+impl ValidationError {
+    /// Stable machine-readable code, independent of the Display message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::MissingField(_) => "missing_field",
+            ValidationError::InvalidValue { .. } => "invalid_value",
+            ValidationError::SummaryTooLong(_) => "summary_too_long",
+            ValidationError::MissingBlockedReason => "missing_blocked_reason",
+            ValidationError::IncompletePartialHandoff => "incomplete_partial_handoff",
+        }
+    }
+}
+
+/// Every problem `KnowledgeManager::validate_handoff_report` found.
+/// Errors make the handoff invalid; warnings are advice only.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub errors: Vec<ValidationError>,
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,25 +62,61 @@ pub struct BriefingInputs {
     pub relevant_findings: Vec<Finding>,
 }
 
+/// Roll-up of a stage's handoffs from
+/// `KnowledgeManager::summarize_stage_handoffs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageSummary {
+    pub stage: Stage,
+    /// Tasks that handed off, in first-seen order.
+    pub task_ids: Vec<String>,
+    /// Findings from every handoff, duplicates (same type and summary)
+    /// dropped. Each keeps the task that reported it.
+    pub findings: Vec<Finding>,
+    pub artifacts: Vec<String>,
+    /// Questions still listed in each task's latest handoff.
+    pub open_questions: Vec<String>,
+}
+
 pub struct KnowledgeManager {
     counter: TokenCounter,
     budgets: HashMap<String, TokenBudget>,
     checkpoints: Vec<Checkpoint>,
     deltas: Vec<Delta>,
     findings: Vec<Finding>,
+    /// Store only findings new since the previous checkpoint.
+    incremental_checkpoints: bool,
+    /// Share of a worker's remaining budget kept for the work itself by
+    /// `compile_briefing_for_worker`; the briefing gets the rest.
+    work_reserve_fraction: f32,
 }
 
 impl KnowledgeManager {
+    /// Falls back to approximate token counts if the tokenizer can't load,
+    /// so constructing a manager never panics.
     pub fn new() -> Self {
         Self {
-            counter: TokenCounter::new(),
+            counter: TokenCounter::new_or_approximate(),
             budgets: HashMap::new(),
             checkpoints: Vec::new(),
             deltas: Vec::new(),
             findings: Vec::new(),
+            incremental_checkpoints: false,
+            work_reserve_fraction: 0.8,
         }
     }
 
+    /// Fraction (0..=1) of a worker's remaining budget that
+    /// `compile_briefing_for_worker` leaves for the task. Defaults to 0.8.
+    pub fn set_work_reserve_fraction(&mut self, fraction: f32) {
+        self.work_reserve_fraction = fraction.clamp(0.0, 1.0);
+    }
+
+    /// When enabled, `create_checkpoint` drops findings already captured by
+    /// earlier checkpoints, keeping the chain compact.
+    pub fn set_incremental_checkpoints(&mut self, incremental: bool) {
+        self.incremental_checkpoints = incremental;
+    }
+
     // Token management
     pub fn count_tokens(&self, text: &str) -> usize {
         self.counter.count(text)
@@ -62,6 +129,13 @@ impl KnowledgeManager {
         );
     }
 
+    pub fn create_budget_for_stage(&mut self, worker_id: &str, stage: Stage) {
+        self.budgets.insert(
+            worker_id.to_string(),
+            TokenBudget::default_for_stage(worker_id, stage),
+        );
+    }
+
     pub fn record_usage(&mut self, worker_id: &str, tokens: usize) {
         if let Some(budget) = self.budgets.get_mut(worker_id) {
             budget.record(tokens);
@@ -76,36 +150,118 @@ impl KnowledgeManager {
         self.budgets.get(worker_id)
     }
 
+    pub fn snapshot_budget(&self, worker_id: &str) -> Option<BudgetSnapshot> {
+        self.budgets.get(worker_id).map(|b| b.snapshot())
+    }
+
+    /// Roll a worker's budget back to `snapshot`. Returns false when the
+    /// worker has no budget.
+    pub fn restore_budget(&mut self, worker_id: &str, snapshot: BudgetSnapshot) -> bool {
+        match self.budgets.get_mut(worker_id) {
+            Some(budget) => {
+                budget.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Tokens spent across every worker budget.
+    pub fn total_used(&self) -> usize {
+        self.budgets.values().map(|b| b.used).sum()
+    }
+
+    /// Token allowance across every worker budget.
+    pub fn total_budget(&self) -> usize {
+        self.budgets.values().map(|b| b.budget).sum()
+    }
+
+    /// Status of the mission as a whole: the aggregate of every budget,
+    /// reservations included, judged with the default thresholds.
+    /// Healthy when no budgets exist.
+    pub fn mission_status(&self) -> BudgetStatus {
+        if self.budgets.is_empty() {
+            return BudgetStatus::Healthy;
+        }
+        let mut mission = TokenBudget::new("mission", self.total_budget());
+        mission.used = self.total_used();
+        mission.reserved = self.budgets.values().map(|b| b.reserved).sum();
+        mission.status()
+    }
+
+    /// Consolidate the budget of `from` into `into` and drop `from`. If
+    /// `into` has no budget yet, `from`'s is moved over under the new id.
+    /// Returns false when `from` has no budget or the ids are equal.
+    pub fn merge_budgets(&mut self, into: &str, from: &str) -> bool {
+        if into == from {
+            return false;
+        }
+        let Some(mut source) = self.budgets.remove(from) else {
+            return false;
+        };
+        match self.budgets.get_mut(into) {
+            Some(target) => target.merge(&source),
+            None => {
+                source.worker_id = into.to_string();
+                self.budgets.insert(into.to_string(), source);
+            }
+        }
+        true
+    }
+
     // Handoff validation
+    /// Fails with the first error `validate_handoff_report` finds.
     pub fn validate_handoff(&self, handoff: &Handoff) -> Result<(), ValidationError> {
+        match self.validate_handoff_report(handoff).errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Check a handoff, collecting every error and warning instead of
+    /// stopping at the first.
+    pub fn validate_handoff_report(&self, handoff: &Handoff) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
         // Validate task_id is present
         if handoff.task_id.is_empty() {
-            return Err(ValidationError::MissingField("task_id".to_string()));
+            report.errors.push(ValidationError::MissingField("task_id".to_string()));
         }
 
         // Validate worker_id is present
         if handoff.worker_id.is_empty() {
-            return Err(ValidationError::MissingField("worker_id".to_string()));
+            report.errors.push(ValidationError::MissingField("worker_id".to_string()));
         }
 
         // Validate blocked status has reason
         if let crate::handoff::HandoffStatus::Blocked(reason) = &handoff.status {
             if reason.is_empty() {
-                return Err(ValidationError::MissingBlockedReason);
+                report.errors.push(ValidationError::MissingBlockedReason);
             }
         }
 
+        // Validate partial status explains what's left
+        if handoff.status == crate::handoff::HandoffStatus::Partial
+            && handoff.open_questions.is_empty()
+            && handoff.context_for_successor.as_ref().is_none_or(|c| c.is_empty())
+        {
+            report.errors.push(ValidationError::IncompletePartialHandoff);
+        }
+
         // Validate finding summaries
+        if handoff.findings.is_empty() {
+            report.warnings.push("No findings reported - consider documenting discoveries".to_string());
+        }
         for finding in &handoff.findings {
             if finding.summary.len() > 500 {
-                return Err(ValidationError::SummaryTooLong(finding.summary.len()));
+                report.errors.push(ValidationError::SummaryTooLong(finding.summary.len()));
             }
             if finding.summary.is_empty() {
-                return Err(ValidationError::MissingField("finding.summary".to_string()));
+                report.errors.push(ValidationError::MissingField("finding.summary".to_string()));
             }
         }
 
-        Ok(())
+        report
     }
 
     // Checkpoint management
@@ -116,14 +272,54 @@ impl KnowledgeManager {
         findings: &[Finding],
     ) -> String {
         let id = format!("cp-{}-{}", stage.as_str(), self.checkpoints.len());
-        let checkpoint = Checkpoint::new(&id, stage)
+
+        let findings = match self.checkpoints.last() {
+            Some(previous) if self.incremental_checkpoints => {
+                let known = self.full_findings_snapshot(&previous.id);
+                findings.iter()
+                    .filter(|f| !known.iter().any(|k| k.same_as(f)))
+                    .cloned()
+                    .collect()
+            }
+            _ => findings.to_vec(),
+        };
+
+        let mut checkpoint = Checkpoint::new(&id, stage)
             .with_tasks(tasks.to_vec())
-            .with_findings(findings.to_vec());
+            .with_findings(findings);
+        checkpoint.findings_incremental = self.incremental_checkpoints;
 
         self.checkpoints.push(checkpoint);
         id
     }
 
+    /// Every finding captured as of a checkpoint, reassembling incremental
+    /// snapshots from the chain. Empty if the checkpoint is unknown.
+    pub fn full_findings_snapshot(&self, checkpoint_id: &str) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = Vec::new();
+        for cp in &self.checkpoints {
+            if !cp.findings_incremental {
+                findings.clear();
+            }
+            findings.extend(cp.findings_snapshot.iter().cloned());
+            if cp.id == checkpoint_id {
+                return findings;
+            }
+        }
+        Vec::new()
+    }
+
+    /// Snapshot the stage a transition just completed, if the engine
+    /// recommends it. Captures `tasks` and all stored findings; returns
+    /// the new checkpoint id.
+    pub fn checkpoint_on_transition(&mut self, outcome: &TransitionOutcome, tasks: &[Task]) -> Option<String> {
+        if !outcome.snapshot_recommended {
+            return None;
+        }
+        let findings = self.findings.clone();
+        Some(self.create_checkpoint(outcome.from, tasks, &findings))
+    }
+
     pub fn get_checkpoint(&self, id: &str) -> Option<&Checkpoint> {
         self.checkpoints.iter().find(|cp| cp.id == id)
     }
@@ -144,8 +340,21 @@ impl KnowledgeManager {
             .with_files(files.to_vec())
     }
 
-    pub fn store_delta(&mut self, delta: Delta) {
+    /// Store a delta after pruning anything its base checkpoint already
+    /// captured. Returns false if nothing new was left to store.
+    pub fn store_delta(&mut self, delta: Delta) -> bool {
+        let delta = match self.get_checkpoint(&delta.from_checkpoint) {
+            Some(base) => {
+                let findings = self.full_findings_snapshot(&base.id);
+                delta.prune_against_parts(&findings, &base.decisions)
+            }
+            None => delta,
+        };
+        if delta.is_empty() {
+            return false;
+        }
         self.deltas.push(delta);
+        true
     }
 
     pub fn get_deltas_since(&self, checkpoint_id: &str) -> Vec<&Delta> {
@@ -154,6 +363,18 @@ impl KnowledgeManager {
             .collect()
     }
 
+    /// Deltas created in `[start, end)`, in the order they were stored.
+    pub fn deltas_between(&self, start: u64, end: u64) -> Vec<&Delta> {
+        self.deltas.iter()
+            .filter(|d| d.created_at >= start && d.created_at < end)
+            .collect()
+    }
+
+    /// Deltas created at or after `start`.
+    pub fn deltas_since_time(&self, start: u64) -> Vec<&Delta> {
+        self.deltas_between(start, u64::MAX)
+    }
+
     // Finding management
     pub fn store_finding(&mut self, finding: Finding) {
         self.findings.push(finding);
@@ -163,7 +384,118 @@ impl KnowledgeManager {
         &self.findings
     }
 
+    /// Up to `limit` findings starting at `offset`, in insertion order.
+    /// Empty past the end.
+    pub fn findings_page(&self, offset: usize, limit: usize) -> &[Finding] {
+        let start = offset.min(self.findings.len());
+        let end = start.saturating_add(limit).min(self.findings.len());
+        &self.findings[start..end]
+    }
+
+    pub fn findings_count(&self) -> usize {
+        self.findings.len()
+    }
+
+    pub fn findings_for_task(&self, task_id: &str) -> Vec<&Finding> {
+        self.findings.iter()
+            .filter(|f| f.task_id.as_deref() == Some(task_id))
+            .collect()
+    }
+
+    /// Merge a stage's handoffs into one summary. Handoffs are read oldest
+    /// first; a task's questions count as open only if its latest handoff
+    /// still lists them.
+    pub fn summarize_stage_handoffs(&self, stage: Stage, handoffs: &[Handoff]) -> StageSummary {
+        let mut ordered: Vec<&Handoff> = handoffs.iter().collect();
+        ordered.sort_by_key(|h| h.timestamp);
+
+        let mut summary = StageSummary {
+            stage,
+            task_ids: Vec::new(),
+            findings: Vec::new(),
+            artifacts: Vec::new(),
+            open_questions: Vec::new(),
+        };
+        for handoff in &ordered {
+            if !summary.task_ids.contains(&handoff.task_id) {
+                summary.task_ids.push(handoff.task_id.clone());
+            }
+            for finding in &handoff.findings {
+                if !summary.findings.iter().any(|f| f.same_as(finding)) {
+                    let mut finding = finding.clone();
+                    finding.task_id.get_or_insert_with(|| handoff.task_id.clone());
+                    summary.findings.push(finding);
+                }
+            }
+            for artifact in &handoff.artifacts {
+                if !summary.artifacts.contains(artifact) {
+                    summary.artifacts.push(artifact.clone());
+                }
+            }
+        }
+
+        for task_id in &summary.task_ids {
+            let latest = ordered.iter().rev().find(|h| &h.task_id == task_id);
+            for question in latest.iter().flat_map(|h| &h.open_questions) {
+                if !summary.open_questions.contains(question) {
+                    summary.open_questions.push(question.clone());
+                }
+            }
+        }
+
+        summary
+    }
+
+    /// Render all stored findings as a markdown report, grouped by type.
+    /// Groups follow `FindingType::all()` order and findings keep insertion
+    /// order within a group, so output is stable across runs.
+    pub fn export_findings_markdown(&self) -> String {
+        let mut out = String::from("# Findings\n");
+
+        if self.findings.is_empty() {
+            out.push_str("\n_No findings recorded._\n");
+            return out;
+        }
+
+        for finding_type in FindingType::all() {
+            let group: Vec<&Finding> = self.findings.iter()
+                .filter(|f| &f.finding_type == finding_type)
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+
+            out.push_str(&format!("\n## {} ({})\n", finding_type.as_str(), group.len()));
+            for f in group {
+                out.push_str(&format!("- {}", f.summary));
+                if let Some(ref severity) = f.severity {
+                    out.push_str(&format!(" **[{}]**", severity));
+                }
+                if let Some(ref path) = f.details_path {
+                    out.push_str(&format!(" ([details]({}))", path));
+                }
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+
     // Briefing compilation
+    /// Briefing for `task` sized to the worker's budget: whatever the
+    /// work reserve leaves of `TokenBudget::remaining()`. Workers without
+    /// a budget get the untrimmed briefing.
+    pub fn compile_briefing_for_worker(&self, worker_id: &str, task: &Task) -> String {
+        let inputs = self.compile_briefing_inputs(task);
+        let max_tokens = match self.budgets.get(worker_id) {
+            // 0 means "no limit" to the compiler, so an exhausted budget
+            // still asks for the smallest possible briefing
+            Some(budget) => ((budget.remaining() as f32 * (1.0 - self.work_reserve_fraction)) as usize).max(1),
+            None => 0,
+        };
+        CheckpointCompiler::compile_briefing(&inputs, &self.counter, max_tokens)
+    }
+
     pub fn compile_briefing_inputs(&self, task: &Task) -> BriefingInputs {
         let checkpoint = self.latest_checkpoint().cloned();
 
@@ -197,7 +529,7 @@ impl Default for KnowledgeManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::handoff::Finding;
+    use crate::handoff::{Finding, SuccessorContext};
 
     #[test]
     fn test_manager_creation() {
@@ -221,6 +553,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_merge_budgets() {
+        let mut manager = KnowledgeManager::new();
+        manager.create_budget("worker-1", 20000);
+        manager.create_budget("Worker-1", 10000);
+        manager.record_usage("worker-1", 3000);
+        manager.record_usage("Worker-1", 4000);
+
+        assert!(manager.merge_budgets("worker-1", "Worker-1"));
+        assert!(manager.get_budget("Worker-1").is_none());
+        let budget = manager.get_budget("worker-1").unwrap();
+        assert_eq!(budget.used, 7000);
+        assert_eq!(budget.budget, 20000);
+
+        assert!(!manager.merge_budgets("worker-1", "worker-1"));
+        assert!(!manager.merge_budgets("worker-1", "missing"));
+
+        assert!(manager.merge_budgets("worker-2", "worker-1"));
+        assert_eq!(manager.get_budget("worker-2").unwrap().worker_id, "worker-2");
+    }
+
+    #[test]
+    fn test_create_budget_for_stage() {
+        let mut manager = KnowledgeManager::new();
+        manager.create_budget_for_stage("worker-1", Stage::Implement);
+
+        let budget = manager.get_budget("worker-1").unwrap();
+        assert_eq!(budget.budget, TokenBudget::default_for_stage("worker-1", Stage::Implement).budget);
+    }
+
     #[test]
     fn test_handoff_validation_success() {
         let manager = KnowledgeManager::new();
@@ -241,6 +603,43 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_handoff_validation_report_collects_everything() {
+        let manager = KnowledgeManager::new();
+        let handoff = Handoff::blocked("", "", "");
+        let report = manager.validate_handoff_report(&handoff);
+        assert!(!report.is_valid());
+        let codes: Vec<&str> = report.errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes, vec!["missing_field", "missing_field", "missing_blocked_reason"]);
+        assert_eq!(report.warnings.len(), 1);
+
+        let report = manager.validate_handoff_report(&Handoff::complete("task-1", "worker-1"));
+        assert!(report.is_valid());
+        assert_eq!(report.warnings, vec!["No findings reported - consider documenting discoveries".to_string()]);
+    }
+
+    #[test]
+    fn test_handoff_validation_partial_needs_context() {
+        let manager = KnowledgeManager::new();
+        let handoff = Handoff::partial("task-1", "worker-1");
+        assert!(matches!(
+            manager.validate_handoff(&handoff),
+            Err(ValidationError::IncompletePartialHandoff)
+        ));
+
+        let with_empty_context = Handoff::partial("task-1", "worker-1")
+            .with_successor_context(SuccessorContext::new());
+        assert!(manager.validate_handoff(&with_empty_context).is_err());
+
+        let with_question = Handoff::partial("task-1", "worker-1")
+            .with_question("Which auth provider?");
+        assert!(manager.validate_handoff(&with_question).is_ok());
+
+        let with_context = Handoff::partial("task-1", "worker-1")
+            .with_successor_context(SuccessorContext::new().with_gotcha("Tests are slow"));
+        assert!(manager.validate_handoff(&with_context).is_ok());
+    }
+
     #[test]
     fn test_handoff_validation_summary_too_long() {
         let manager = KnowledgeManager::new();
@@ -254,6 +653,21 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_checkpoint_on_transition() {
+        let mut manager = KnowledgeManager::new();
+        manager.store_finding(Finding::discovery("Found the API"));
+
+        let mut outcome = TransitionOutcome { from: Stage::Discovery, to: Stage::Goal, snapshot_recommended: true };
+        let id = manager.checkpoint_on_transition(&outcome, &[]).unwrap();
+        let checkpoint = manager.get_checkpoint(&id).unwrap();
+        assert_eq!(checkpoint.stage, Stage::Discovery);
+        assert_eq!(checkpoint.findings_snapshot.len(), 1);
+
+        outcome.snapshot_recommended = false;
+        assert!(manager.checkpoint_on_transition(&outcome, &[]).is_none());
+    }
+
     #[test]
     fn test_checkpoint_creation() {
         let mut manager = KnowledgeManager::new();
@@ -264,6 +678,138 @@ mod tests {
         assert!(manager.latest_checkpoint().is_some());
     }
 
+    #[test]
+    fn test_incremental_checkpoints() {
+        let mut manager = KnowledgeManager::new();
+        manager.set_incremental_checkpoints(true);
+        let first = [Finding::discovery("A"), Finding::discovery("B")];
+        let second = [Finding::discovery("A"), Finding::discovery("B"), Finding::concern("C")];
+
+        let cp1 = manager.create_checkpoint(Stage::Design, &[], &first);
+        let cp2 = manager.create_checkpoint(Stage::Design, &[], &second);
+
+        let stored: Vec<&str> = manager.get_checkpoint(&cp2).unwrap()
+            .findings_snapshot.iter().map(|f| f.summary.as_str()).collect();
+        assert_eq!(stored, vec!["C"]);
+
+        let full: Vec<String> = manager.full_findings_snapshot(&cp2).into_iter().map(|f| f.summary).collect();
+        assert_eq!(full, vec!["A", "B", "C"]);
+        assert_eq!(manager.full_findings_snapshot(&cp1).len(), 2);
+        assert!(manager.full_findings_snapshot("cp-missing").is_empty());
+    }
+
+    #[test]
+    fn test_summarize_stage_handoffs() {
+        let manager = KnowledgeManager::new();
+        let mut first = Handoff::partial("task-1", "worker-1");
+        first.timestamp = 10;
+        first.findings = vec![Finding::discovery("Uses JWT")];
+        first.artifacts = vec!["src/auth.rs".to_string()];
+        first.open_questions = vec!["Which IdP?".to_string()];
+
+        let mut second = Handoff::complete("task-1", "worker-2");
+        second.timestamp = 20;
+        second.findings = vec![Finding::discovery("Uses JWT"), Finding::concern("Tokens never expire")];
+        second.artifacts = vec!["src/auth.rs".to_string(), "docs/auth.md".to_string()];
+
+        let mut other = Handoff::partial("task-2", "worker-3");
+        other.timestamp = 15;
+        other.open_questions = vec!["Rate limits?".to_string()];
+
+        let summary = manager.summarize_stage_handoffs(Stage::Implement, &[second, other, first]);
+        assert_eq!(summary.stage, Stage::Implement);
+        assert_eq!(summary.task_ids, vec!["task-1", "task-2"]);
+        assert_eq!(summary.findings.len(), 2);
+        assert_eq!(summary.findings[0].task_id.as_deref(), Some("task-1"));
+        assert_eq!(summary.artifacts, vec!["src/auth.rs", "docs/auth.md"]);
+        assert_eq!(summary.open_questions, vec!["Rate limits?"]);
+    }
+
+    #[test]
+    fn test_snapshot_and_restore_budget() {
+        let mut manager = KnowledgeManager::new();
+        manager.create_budget("worker-1", 1000);
+        manager.record_usage("worker-1", 200);
+        let snapshot = manager.snapshot_budget("worker-1").unwrap();
+
+        manager.record_usage("worker-1", 700);
+        assert!(manager.restore_budget("worker-1", snapshot));
+        assert_eq!(manager.get_budget("worker-1").unwrap().used, 200);
+
+        assert!(manager.snapshot_budget("missing").is_none());
+        assert!(!manager.restore_budget("missing", snapshot));
+    }
+
+    #[test]
+    fn test_mission_budget_rollup() {
+        let mut manager = KnowledgeManager::new();
+        assert_eq!(manager.mission_status(), BudgetStatus::Healthy);
+
+        manager.create_budget("worker-1", 1000);
+        manager.create_budget("worker-2", 3000);
+        manager.record_usage("worker-1", 900);
+        assert_eq!(manager.check_budget("worker-1"), Some(BudgetStatus::Critical { remaining: 100 }));
+        assert_eq!(manager.total_used(), 900);
+        assert_eq!(manager.total_budget(), 4000);
+        assert_eq!(manager.mission_status(), BudgetStatus::Healthy);
+
+        manager.record_usage("worker-2", 1500);
+        assert_eq!(manager.mission_status(), BudgetStatus::Warning { remaining: 1600 });
+    }
+
+    #[test]
+    fn test_compile_briefing_for_worker() {
+        let mut manager = KnowledgeManager::new();
+        let decisions: Vec<String> = (0..40).map(|i| format!("Decision number {} about the API", i)).collect();
+        let checkpoint = Checkpoint::new("cp-0", Stage::Implement).with_decisions(decisions);
+        manager.checkpoints.push(checkpoint);
+        let task = Task::new("task-1", "Build API", Stage::Implement, "backend", "developer");
+
+        let full = manager.compile_briefing_for_worker("unbudgeted", &task);
+        let full_tokens = manager.count_tokens(&full);
+
+        manager.create_budget("worker-1", 1000);
+        let briefing = manager.compile_briefing_for_worker("worker-1", &task);
+        assert!(manager.count_tokens(&briefing) <= 200);
+        assert!(manager.count_tokens(&briefing) < full_tokens);
+
+        manager.set_work_reserve_fraction(0.0);
+        let roomy = manager.compile_briefing_for_worker("worker-1", &task);
+        assert!(manager.count_tokens(&roomy) > manager.count_tokens(&briefing));
+    }
+
+    #[test]
+    fn test_findings_page() {
+        let mut manager = KnowledgeManager::new();
+        for i in 0..5 {
+            manager.store_finding(Finding::discovery(format!("Finding {}", i)));
+        }
+
+        assert_eq!(manager.findings_count(), 5);
+        let page: Vec<&str> = manager.findings_page(2, 2).iter().map(|f| f.summary.as_str()).collect();
+        assert_eq!(page, vec!["Finding 2", "Finding 3"]);
+        assert_eq!(manager.findings_page(4, 10).len(), 1);
+        assert!(manager.findings_page(9, 2).is_empty());
+        assert_eq!(manager.findings_page(0, usize::MAX).len(), 5);
+    }
+
+    #[test]
+    fn test_store_delta_drops_redundant() {
+        let mut manager = KnowledgeManager::new();
+        let cp_id = manager.create_checkpoint(Stage::Design, &[], &[Finding::discovery("Known")]);
+
+        let redundant = Delta::new(&cp_id).with_findings(vec![Finding::discovery("Known")]);
+        assert!(!manager.store_delta(redundant));
+        assert!(manager.get_deltas_since(&cp_id).is_empty());
+
+        let mixed = Delta::new(&cp_id)
+            .with_findings(vec![Finding::discovery("Known"), Finding::concern("New")]);
+        assert!(manager.store_delta(mixed));
+        let deltas = manager.get_deltas_since(&cp_id);
+        assert_eq!(deltas[0].new_findings.len(), 1);
+        assert_eq!(deltas[0].new_findings[0].summary, "New");
+    }
+
     #[test]
     fn test_delta_management() {
         let mut manager = KnowledgeManager::new();
@@ -280,4 +826,65 @@ mod tests {
         let deltas = manager.get_deltas_since(&cp_id);
         assert_eq!(deltas.len(), 1);
     }
+
+    #[test]
+    fn test_deltas_by_time() {
+        let mut manager = KnowledgeManager::new();
+        let cp_id = manager.create_checkpoint(Stage::Design, &[], &[]);
+        for (at, summary) in [(100, "First"), (200, "Second"), (300, "Third")] {
+            let mut delta = Delta::new(&cp_id).with_findings(vec![Finding::discovery(summary)]);
+            delta.created_at = at;
+            manager.store_delta(delta);
+        }
+
+        let summaries = |deltas: Vec<&Delta>| -> Vec<String> {
+            deltas.iter().map(|d| d.new_findings[0].summary.clone()).collect()
+        };
+        assert_eq!(summaries(manager.deltas_between(100, 300)), vec!["First", "Second"]);
+        assert_eq!(summaries(manager.deltas_since_time(200)), vec!["Second", "Third"]);
+        assert!(manager.deltas_between(301, 400).is_empty());
+    }
+
+    #[test]
+    fn test_findings_for_task() {
+        let mut manager = KnowledgeManager::new();
+        manager.store_finding(Finding::discovery("From task 1").with_task("task-1"));
+        manager.store_finding(Finding::discovery("From task 2").with_task("task-2"));
+        manager.store_finding(Finding::discovery("Unlinked"));
+
+        let findings = manager.findings_for_task("task-1");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].summary, "From task 1");
+        assert!(manager.findings_for_task("task-3").is_empty());
+    }
+
+    #[test]
+    fn test_export_findings_markdown() {
+        let mut manager = KnowledgeManager::new();
+        manager.store_finding(Finding::concern("Slow query").with_severity("high"));
+        manager.store_finding(Finding::discovery("Auth exists").with_details(".mission/findings/auth.md"));
+        manager.store_finding(Finding::discovery("Cache exists"));
+
+        let report = manager.export_findings_markdown();
+        assert!(report.starts_with("# Findings\n"));
+        assert!(report.contains("## discovery (2)"));
+        assert!(report.contains("- Auth exists ([details](.mission/findings/auth.md))"));
+        assert!(report.contains("- Slow query **[high]**"));
+        assert!(!report.contains("## blocker"));
+
+        // Discovery group precedes concern regardless of insertion order
+        let discovery = report.find("## discovery").unwrap();
+        let concern = report.find("## concern").unwrap();
+        assert!(discovery < concern);
+        assert!(report.find("Auth exists").unwrap() < report.find("Cache exists").unwrap());
+
+        assert_eq!(report, manager.export_findings_markdown());
+    }
+
+    #[test]
+    fn test_export_findings_markdown_empty() {
+        let manager = KnowledgeManager::new();
+        let report = manager.export_findings_markdown();
+        assert!(report.contains("No findings recorded"));
+    }
 }
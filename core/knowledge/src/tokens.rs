@@ -1,18 +1,120 @@
+use thiserror::Error;
 use tiktoken_rs::cl100k_base;
 
+#[derive(Debug, Error)]
+pub enum TokenError {
+    #[error("Failed to initialize tokenizer: {0}")]
+    InitFailed(String),
+
+    #[error("Token ids are not available from the approximate counter")]
+    NoTokenizer,
+
+    #[error("Failed to decode tokens: {0}")]
+    DecodeFailed(String),
+
+    #[error("Token id {0} is not in the vocabulary")]
+    UnknownToken(u32),
+}
+
+/// cl100k_base ranks are contiguous from 0; special tokens sit above them.
+const CL100K_RANKS: u32 = 100_256;
+const CL100K_SPECIAL_TOKENS: [u32; 5] = [100_257, 100_258, 100_259, 100_260, 100_276];
+
+fn in_cl100k_vocabulary(token: u32) -> bool {
+    token < CL100K_RANKS || CL100K_SPECIAL_TOKENS.contains(&token)
+}
+
+/// Rough token estimate (~4 chars per token) for environments where the
+/// BPE tables can't be loaded.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproximateCounter;
+
+impl ApproximateCounter {
+    pub fn count(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+enum Backend {
+    Bpe(Box<tiktoken_rs::CoreBPE>),
+    Approximate(ApproximateCounter),
+}
+
 pub struct TokenCounter {
-    bpe: tiktoken_rs::CoreBPE,
+    backend: Backend,
 }
 
 impl TokenCounter {
+    /// Create a cl100k_base counter. Panics if the tokenizer fails to load;
+    /// use `try_new` or `approximate` where that is not acceptable.
     pub fn new() -> Self {
+        Self::try_new().expect("Failed to initialize tiktoken")
+    }
+
+    pub fn try_new() -> Result<Self, TokenError> {
+        let bpe = cl100k_base().map_err(|e| TokenError::InitFailed(e.to_string()))?;
+        Ok(Self {
+            backend: Backend::Bpe(Box::new(bpe)),
+        })
+    }
+
+    /// Counter using the chars/4 heuristic instead of a real tokenizer.
+    pub fn approximate() -> Self {
         Self {
-            bpe: cl100k_base().expect("Failed to initialize tiktoken"),
+            backend: Backend::Approximate(ApproximateCounter),
         }
     }
 
+    /// Exact counter if the tokenizer loads, otherwise the approximation.
+    pub fn new_or_approximate() -> Self {
+        Self::try_new().unwrap_or_else(|_| Self::approximate())
+    }
+
+    pub fn is_approximate(&self) -> bool {
+        matches!(self.backend, Backend::Approximate(_))
+    }
+
+    /// Count tokens treating special tokens such as `<|endoftext|>` as
+    /// single tokens, as a prompt would be encoded.
     pub fn count(&self, text: &str) -> usize {
-        self.bpe.encode_with_special_tokens(text).len()
+        match &self.backend {
+            Backend::Bpe(bpe) => bpe.encode_with_special_tokens(text).len(),
+            Backend::Approximate(approx) => approx.count(text),
+        }
+    }
+
+    /// Count tokens treating special-token strings as plain text. More
+    /// accurate for budgeting user content that may contain them literally.
+    pub fn count_ordinary(&self, text: &str) -> usize {
+        match &self.backend {
+            Backend::Bpe(bpe) => bpe.encode_ordinary(text).len(),
+            Backend::Approximate(approx) => approx.count(text),
+        }
+    }
+
+    /// Token ids for `text`, as counted by `count`. Useful for seeing where
+    /// the tokenizer splits a string.
+    pub fn encode(&self, text: &str) -> Result<Vec<u32>, TokenError> {
+        match &self.backend {
+            Backend::Bpe(bpe) => Ok(bpe.encode_with_special_tokens(text)
+                .into_iter()
+                .map(|t| t as u32)
+                .collect()),
+            Backend::Approximate(_) => Err(TokenError::NoTokenizer),
+        }
+    }
+
+    pub fn decode(&self, tokens: &[u32]) -> Result<String, TokenError> {
+        let bpe = match &self.backend {
+            Backend::Bpe(bpe) => bpe,
+            Backend::Approximate(_) => return Err(TokenError::NoTokenizer),
+        };
+        // tiktoken panics on ids outside the vocabulary
+        if let Some(&unknown) = tokens.iter().find(|&&t| !in_cl100k_vocabulary(t)) {
+            return Err(TokenError::UnknownToken(unknown));
+        }
+        let tokens: Vec<usize> = tokens.iter().map(|&t| t as usize).collect();
+        bpe.decode(tokens).map_err(|e| TokenError::DecodeFailed(e.to_string()))
     }
 }
 
@@ -45,4 +147,45 @@ mod tests {
         let count = counter.count(text);
         assert!(count > 10);
     }
+
+    #[test]
+    fn test_count_ordinary_treats_special_tokens_as_text() {
+        let counter = TokenCounter::new();
+        let text = "before <|endoftext|> after";
+        assert!(counter.count_ordinary(text) > counter.count(text));
+        assert_eq!(counter.count_ordinary("hello world"), counter.count("hello world"));
+
+        let approx = TokenCounter::approximate();
+        assert_eq!(approx.count_ordinary(text), approx.count(text));
+    }
+
+    #[test]
+    fn test_try_new() {
+        let counter = TokenCounter::try_new().unwrap();
+        assert!(!counter.is_approximate());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let counter = TokenCounter::new();
+        let text = "Tokenization surprises: naïve café";
+        let tokens = counter.encode(text).unwrap();
+        assert_eq!(tokens.len(), counter.count(text));
+        assert_eq!(counter.decode(&tokens).unwrap(), text);
+
+        assert!(matches!(counter.decode(&[u32::MAX]), Err(TokenError::UnknownToken(u32::MAX))));
+        assert!(matches!(counter.decode(&[100_256]), Err(TokenError::UnknownToken(100_256))));
+        assert_eq!(counter.decode(&[100_257]).unwrap(), "<|endoftext|>");
+        assert!(matches!(TokenCounter::approximate().encode(text), Err(TokenError::NoTokenizer)));
+    }
+
+    #[test]
+    fn test_approximate_counter() {
+        let counter = TokenCounter::approximate();
+        assert!(counter.is_approximate());
+        assert_eq!(counter.count(""), 0);
+        assert_eq!(counter.count("abcd"), 1);
+        assert_eq!(counter.count("abcde"), 2);
+        assert_eq!(counter.count("héllo wörld!"), 3);
+    }
 }
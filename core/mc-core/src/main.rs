@@ -1,12 +1,13 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use globset::Glob;
 use knowledge::{Handoff, HandoffStatus, TokenCounter, Checkpoint};
 use knowledge::checkpoint::CheckpointCompiler;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
-use workflow::{Gate, GateStatus, Stage, Task};
+use workflow::{Gate, GatesFile, GateStatus, Stage, Task, WorkflowEngine, INTEGRATOR_REQUIREMENT, REVIEWER_REQUIREMENT};
 
 #[derive(Parser)]
 #[command(name = "mc-core")]
@@ -18,10 +19,17 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Validate a handoff JSON file
+    /// Validate handoff JSON files
     ValidateHandoff {
-        /// Path to the handoff JSON file
-        file: PathBuf,
+        /// Paths to handoff JSON files. More than one prints an array of results
+        #[arg(required_unless_present = "dir", conflicts_with = "dir")]
+        files: Vec<PathBuf>,
+        /// Validate every *.json file under this directory instead
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Directory that relative artifact and finding paths resolve against
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
     },
     /// Check gate criteria for a stage
     CheckGate {
@@ -36,17 +44,47 @@ enum Commands {
         /// Path to file, or "-" for stdin (default: stdin)
         #[arg(default_value = "-")]
         source: String,
+        /// Count every file under this directory instead
+        #[arg(long, conflicts_with = "source")]
+        dir: Option<PathBuf>,
+        /// Only count files whose name matches this pattern (e.g. "*.md")
+        #[arg(long, requires = "dir")]
+        glob: Option<String>,
     },
     /// Compile a checkpoint JSON file into a markdown briefing
     CheckpointCompile {
         /// Path to the checkpoint JSON file
         file: PathBuf,
+        /// Write the briefing to this file (atomically) instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Trim the briefing to fit within this many tokens
+        #[arg(long)]
+        max_tokens: Option<usize>,
     },
     /// Validate a checkpoint JSON file schema
     CheckpointValidate {
         /// Path to the checkpoint JSON file
         file: PathBuf,
     },
+    /// Print the JSON Schema for a payload type
+    #[cfg(feature = "schema")]
+    Schema {
+        /// Payload type to describe
+        #[arg(value_enum)]
+        kind: SchemaKind,
+    },
+}
+
+/// Payload types `mc-core schema` can describe.
+#[cfg(feature = "schema")]
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum SchemaKind {
+    Handoff,
+    Finding,
+    Checkpoint,
+    Delta,
+    Task,
 }
 
 #[derive(Debug, Serialize)]
@@ -56,12 +94,20 @@ struct ValidationResult {
     warnings: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct FileValidationResult {
+    file: String,
+    #[serde(flatten)]
+    result: ValidationResult,
+}
+
 #[derive(Debug, Serialize)]
 struct GateCheckResult {
     stage: String,
     status: String,
     criteria: Vec<CriterionResult>,
     can_approve: bool,
+    blocking_reasons: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -75,14 +121,40 @@ struct TokenCountResult {
     tokens: usize,
 }
 
+#[derive(Debug, Serialize)]
+struct DirTokenCountResult {
+    total: usize,
+    /// Largest first.
+    files: Vec<FileTokenCount>,
+}
+
+#[derive(Debug, Serialize)]
+struct FileTokenCount {
+    path: String,
+    tokens: usize,
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::ValidateHandoff { file } => {
-            let result = validate_handoff(&file)?;
-            println!("{}", serde_json::to_string_pretty(&result)?);
-            if !result.valid {
+        Commands::ValidateHandoff { files, dir, root } => {
+            if let ([file], None) = (files.as_slice(), &dir) {
+                let result = validate_handoff(file, &root)?;
+                println!("{}", serde_json::to_string_pretty(&result)?);
+                if !result.valid {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+
+            let files = match dir {
+                Some(dir) => handoff_files_in_dir(&dir)?,
+                None => files,
+            };
+            let results = validate_handoffs(&files, &root);
+            println!("{}", serde_json::to_string_pretty(&results)?);
+            if results.iter().any(|r| !r.result.valid) {
                 std::process::exit(1);
             }
         }
@@ -90,17 +162,30 @@ fn main() -> Result<()> {
             let result = check_gate(&stage, &mission_dir)?;
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
-        Commands::CountTokens { source } => {
-            let result = count_tokens(&source)?;
-            println!("{}", serde_json::to_string(&result)?);
-        }
-        Commands::CheckpointCompile { file } => {
-            let content = fs::read_to_string(&file)
-                .with_context(|| format!("Failed to read checkpoint file: {}", file.display()))?;
-            let checkpoint: Checkpoint = serde_json::from_str(&content)
-                .with_context(|| "Failed to parse checkpoint JSON")?;
-            let briefing = CheckpointCompiler::compile(&checkpoint);
-            println!("{}", briefing);
+        Commands::CountTokens { source, dir, glob } => match dir {
+            Some(dir) => {
+                let result = count_tokens_in_dir(&dir, glob.as_deref())?;
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            None => {
+                let result = count_tokens(&source)?;
+                println!("{}", serde_json::to_string(&result)?);
+            }
+        },
+        Commands::CheckpointCompile { file, output, max_tokens } => {
+            let compiled = compile_checkpoint(&file, max_tokens)?;
+            if compiled.dropped_tokens > 0 {
+                eprintln!(
+                    "note: briefing trimmed to {} tokens ({} of {} dropped)",
+                    compiled.tokens,
+                    compiled.dropped_tokens,
+                    compiled.tokens + compiled.dropped_tokens
+                );
+            }
+            match output {
+                Some(path) => write_atomic(&path, &compiled.briefing)?,
+                None => println!("{}", compiled.briefing),
+            }
         }
         Commands::CheckpointValidate { file } => {
             let result = validate_checkpoint(&file)?;
@@ -109,12 +194,40 @@ fn main() -> Result<()> {
                 std::process::exit(1);
             }
         }
+        #[cfg(feature = "schema")]
+        Commands::Schema { kind } => {
+            println!("{}", serde_json::to_string_pretty(&json_schema(kind))?);
+        }
     }
 
     Ok(())
 }
 
-fn validate_handoff(file: &PathBuf) -> Result<ValidationResult> {
+/// Validate each file in order, labelling every result with its path. A
+/// file that can't be read is reported as invalid and the rest still run.
+fn validate_handoffs(files: &[PathBuf], root: &Path) -> Vec<FileValidationResult> {
+    files.iter()
+        .map(|file| FileValidationResult {
+            file: file.display().to_string(),
+            result: validate_handoff(file, root).unwrap_or_else(|e| ValidationResult {
+                valid: false,
+                errors: vec![format!("{:#}", e)],
+                warnings: Vec::new(),
+            }),
+        })
+        .collect()
+}
+
+/// Every `*.json` file under `dir`, sorted by path.
+fn handoff_files_in_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    collect_files(dir, &mut paths)?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "json"));
+    paths.sort();
+    Ok(paths)
+}
+
+fn validate_handoff(file: &PathBuf, root: &Path) -> Result<ValidationResult> {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
 
@@ -122,11 +235,15 @@ fn validate_handoff(file: &PathBuf) -> Result<ValidationResult> {
     let content = fs::read_to_string(file)
         .with_context(|| format!("Failed to read file: {}", file.display()))?;
 
-    // Parse JSON
-    let handoff: Handoff = match serde_json::from_str(&content) {
-        Ok(h) => h,
-        Err(e) => {
-            errors.push(format!("Invalid JSON: {}", e));
+    // Parse JSON, salvaging well-formed fields so every problem is reported
+    let (handoff, diagnostics) = Handoff::parse_lenient(&content);
+    let reported = |field: &str| diagnostics.iter().any(|d| d.starts_with(&format!("{}:", field)));
+    let task_id_reported = reported("task_id");
+    let worker_id_reported = reported("worker_id");
+    errors.extend(diagnostics);
+    let handoff = match handoff {
+        Some(h) => h,
+        None => {
             return Ok(ValidationResult {
                 valid: false,
                 errors,
@@ -135,12 +252,12 @@ fn validate_handoff(file: &PathBuf) -> Result<ValidationResult> {
         }
     };
 
-    // Validate required fields
-    if handoff.task_id.is_empty() {
+    // Validate required fields (skip ones already reported as malformed)
+    if handoff.task_id.is_empty() && !task_id_reported {
         errors.push("task_id is required".to_string());
     }
 
-    if handoff.worker_id.is_empty() {
+    if handoff.worker_id.is_empty() && !worker_id_reported {
         errors.push("worker_id is required".to_string());
     }
 
@@ -156,9 +273,17 @@ fn validate_handoff(file: &PathBuf) -> Result<ValidationResult> {
         }
     }
 
+    // Partial handoffs must tell the next worker what's left
+    if handoff.status == HandoffStatus::Partial
+        && handoff.open_questions.is_empty()
+        && handoff.context_for_successor.as_ref().is_none_or(|c| c.is_empty())
+    {
+        errors.push("Partial status requires open questions or context for successor".to_string());
+    }
+
     // Check artifacts exist (warning only)
     for artifact in &handoff.artifacts {
-        if !PathBuf::from(artifact).exists() {
+        if !root.join(artifact).exists() {
             warnings.push(format!("Artifact not found: {}", artifact));
         }
     }
@@ -171,6 +296,11 @@ fn validate_handoff(file: &PathBuf) -> Result<ValidationResult> {
         if finding.summary.len() > 500 {
             warnings.push(format!("Finding {} summary is very long (>500 chars)", i));
         }
+        if let Some(ref path) = finding.details_path {
+            if !root.join(path).exists() {
+                warnings.push(format!("Finding {} details not found: {}", i, path));
+            }
+        }
     }
 
     Ok(ValidationResult {
@@ -217,102 +347,54 @@ fn load_tasks_for_stage(mission_dir: &Path, stage_str: &str, stage: Stage) -> Ve
 
 fn check_gate(stage_str: &str, mission_dir: &Path) -> Result<GateCheckResult> {
     // Parse stage
-    let stage: Stage = serde_json::from_str(&format!("\"{}\"", stage_str))
-        .with_context(|| format!("Invalid stage: {}. Valid: discovery, goal, requirements, planning, design, implement, verify, validate, document, release", stage_str))?;
+    let stage: Stage = stage_str.parse()?;
 
     // Try to read existing gate state
     let gates_file = mission_dir.join("state/gates.json");
-    let gate = if gates_file.exists() {
+    let gates = if gates_file.exists() {
         let content = fs::read_to_string(&gates_file)
             .with_context(|| format!("Failed to read gates file: {}", gates_file.display()))?;
-
-        #[derive(Deserialize)]
-        struct GatesFile {
-            gates: std::collections::HashMap<String, GateState>,
-        }
-
-        /// A single gate criterion — supports both legacy string format
-        /// and the new structured format with description + satisfied.
-        #[derive(Deserialize)]
-        #[serde(untagged)]
-        enum CriterionEntry {
-            Plain(#[allow(dead_code)] String),
-            Structured {
-                #[allow(dead_code)]
-                description: String,
-                satisfied: bool,
-            },
-        }
-
-        #[derive(Deserialize)]
-        struct GateState {
-            criteria: Vec<CriterionEntry>,
-            approved_at: Option<String>,
-            #[allow(dead_code)]
-            status: Option<String>,
-        }
-
-        let gates: GatesFile = serde_json::from_str(&content)?;
-
-        if let Some(state) = gates.gates.get(stage_str) {
-            // Build gate from state — read per-criterion satisfaction
-            let mut gate = Gate::new(stage);
-            for (i, criterion) in gate.criteria.iter_mut().enumerate() {
-                if i < state.criteria.len() {
-                    match &state.criteria[i] {
-                        CriterionEntry::Plain(_) => {
-                            // Legacy string format — can't determine satisfaction, leave unsatisfied
-                        }
-                        CriterionEntry::Structured { satisfied, .. } => {
-                            if *satisfied {
-                                criterion.satisfy();
-                            }
-                        }
-                    }
-                }
-            }
-            if state.approved_at.is_some() {
-                gate.approve("system");
-            }
-            gate
-        } else {
-            Gate::new(stage)
-        }
+        GatesFile::from_json(&content)
+            .with_context(|| format!("Failed to parse gates file: {}", gates_file.display()))?
     } else {
-        Gate::new(stage)
+        GatesFile::default()
     };
 
+    // Load gate and task state into an engine so approval is decided by
+    // the same rules the FFI uses
+    let mut engine = WorkflowEngine::new();
+    for gate in gates.to_engine_gates() {
+        engine.set_gate(gate);
+    }
+    let tasks = load_tasks_for_stage(mission_dir, stage_str, stage);
+    for task in &tasks {
+        engine.create_task(task.clone());
+    }
+    let gate = gates.gate_for(stage);
+
+    // Structural requirements are recomputed from tasks below, so drop any
+    // stale copies that were persisted into gates.json by an earlier check.
     let mut criteria: Vec<CriterionResult> = gate
         .criteria
         .iter()
+        .filter(|c| c.description != INTEGRATOR_REQUIREMENT && c.description != REVIEWER_REQUIREMENT)
         .map(|c| CriterionResult {
             description: c.description.clone(),
             satisfied: c.satisfied,
         })
         .collect();
 
-    // For implement stage, check integrator requirement
-    if stage == Stage::Implement {
-        let tasks = load_tasks_for_stage(mission_dir, "implement", Stage::Implement);
-        let failures = Gate::check_integrator_requirement(&tasks);
-        for f in &failures {
-            criteria.push(CriterionResult {
-                description: f.clone(),
-                satisfied: false,
-            });
-        }
-    }
-
-    // For verify stage, check reviewer requirement
-    if stage == Stage::Verify {
-        let tasks = load_tasks_for_stage(mission_dir, "verify", Stage::Verify);
-        let failures = Gate::check_reviewer_requirement(&tasks);
-        for f in &failures {
-            criteria.push(CriterionResult {
-                description: f.clone(),
-                satisfied: false,
-            });
-        }
+    // Structural requirements: integrator for implement, reviewer for verify
+    let failures = match stage {
+        Stage::Implement => Gate::check_integrator_requirement(&tasks),
+        Stage::Verify => Gate::check_reviewer_requirement(&tasks),
+        _ => Vec::new(),
+    };
+    for f in failures {
+        criteria.push(CriterionResult {
+            description: f,
+            satisfied: false,
+        });
     }
 
     let status = match gate.status {
@@ -321,14 +403,14 @@ fn check_gate(stage_str: &str, mission_dir: &Path) -> Result<GateCheckResult> {
         GateStatus::AwaitingApproval => "awaiting_approval",
     };
 
-    // can_approve must check ALL criteria including appended integrator/reviewer checks
-    let all_satisfied = criteria.iter().all(|c| c.satisfied);
+    let (can_approve, blocking_reasons) = engine.can_approve_gate(stage);
 
     Ok(GateCheckResult {
         stage: stage_str.to_string(),
         status: status.to_string(),
         criteria,
-        can_approve: all_satisfied && gate.approved_at.is_none(),
+        can_approve,
+        blocking_reasons,
     })
 }
 
@@ -352,9 +434,103 @@ fn count_tokens(source: &str) -> Result<TokenCountResult> {
     Ok(TokenCountResult { tokens })
 }
 
+/// Count tokens in every file under `dir` whose name matches `glob`.
+/// Files that aren't valid UTF-8 are skipped.
+fn count_tokens_in_dir(dir: &Path, glob: Option<&str>) -> Result<DirTokenCountResult> {
+    let matcher = glob
+        .map(|pattern| Glob::new(pattern).map(|g| g.compile_matcher()))
+        .transpose()
+        .with_context(|| format!("Invalid glob pattern: {}", glob.unwrap_or_default()))?;
+    let mut paths = Vec::new();
+    collect_files(dir, &mut paths)?;
+
+    let counter = TokenCounter::new();
+    let mut files = Vec::new();
+    for path in paths {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if matcher.as_ref().is_some_and(|m| !m.is_match(name)) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+        files.push(FileTokenCount {
+            path: relative.display().to_string(),
+            tokens: counter.count(&content),
+        });
+    }
+
+    files.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+    let total = files.iter().map(|f| f.tokens).sum();
+    Ok(DirTokenCountResult { total, files })
+}
+
+/// Every file under `dir`, recursively. Symlinks are listed, not followed,
+/// so a link back up the tree can't recurse forever.
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+struct CompiledBriefing {
+    briefing: String,
+    tokens: usize,
+    /// Tokens cut to fit `--max-tokens`, compared with the untrimmed briefing.
+    dropped_tokens: usize,
+}
+
+fn compile_checkpoint(file: &Path, max_tokens: Option<usize>) -> Result<CompiledBriefing> {
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read checkpoint file: {}", file.display()))?;
+    let checkpoint = Checkpoint::from_json(&content)?;
+
+    let counter = TokenCounter::new();
+    let full = CheckpointCompiler::compile(&checkpoint);
+    let full_tokens = CheckpointCompiler::estimated_tokens(&full, &counter);
+
+    let briefing = match max_tokens {
+        Some(max) => CheckpointCompiler::compile_within_budget(&checkpoint, None, max, &counter),
+        None => full,
+    };
+    let tokens = CheckpointCompiler::estimated_tokens(&briefing, &counter);
+
+    Ok(CompiledBriefing {
+        briefing,
+        tokens,
+        dropped_tokens: full_tokens.saturating_sub(tokens),
+    })
+}
+
+/// Write via a temporary sibling file and rename, so readers never see a
+/// partially written file.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let mut tmp_name = path.file_name()
+        .with_context(|| format!("Invalid output path: {}", path.display()))?
+        .to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+
+    fs::write(&tmp, contents)
+        .with_context(|| format!("Failed to write file: {}", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to move {} to {}", tmp.display(), path.display()))?;
+    Ok(())
+}
+
 fn validate_checkpoint(file: &PathBuf) -> Result<ValidationResult> {
     let mut errors = Vec::new();
-    let warnings = Vec::new();
+    let mut warnings = Vec::new();
 
     let content = fs::read_to_string(file)
         .with_context(|| format!("Failed to read file: {}", file.display()))?;
@@ -372,6 +548,10 @@ fn validate_checkpoint(file: &PathBuf) -> Result<ValidationResult> {
         }
     };
 
+    if let Err(e) = checkpoint.check_version() {
+        errors.push(e.to_string());
+    }
+
     // Validate required fields
     if checkpoint.id.is_empty() {
         errors.push("id is required".to_string());
@@ -381,6 +561,11 @@ fn validate_checkpoint(file: &PathBuf) -> Result<ValidationResult> {
         errors.push("created_at must be non-zero".to_string());
     }
 
+    match checkpoint.check_stage_consistency() {
+        Ok(stage_warnings) => warnings.extend(stage_warnings),
+        Err(e) => errors.push(e.to_string()),
+    }
+
     Ok(ValidationResult {
         valid: errors.is_empty(),
         errors,
@@ -388,6 +573,17 @@ fn validate_checkpoint(file: &PathBuf) -> Result<ValidationResult> {
     })
 }
 
+#[cfg(feature = "schema")]
+fn json_schema(kind: SchemaKind) -> schemars::schema::RootSchema {
+    match kind {
+        SchemaKind::Handoff => schemars::schema_for!(Handoff),
+        SchemaKind::Finding => schemars::schema_for!(knowledge::Finding),
+        SchemaKind::Checkpoint => schemars::schema_for!(Checkpoint),
+        SchemaKind::Delta => schemars::schema_for!(knowledge::Delta),
+        SchemaKind::Task => schemars::schema_for!(Task),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -415,7 +611,7 @@ mod tests {
         let mut file = NamedTempFile::new().unwrap();
         file.write_all(handoff.as_bytes()).unwrap();
 
-        let result = validate_handoff(&file.path().to_path_buf()).unwrap();
+        let result = validate_handoff(&file.path().to_path_buf(), Path::new(".")).unwrap();
         assert!(result.valid);
         assert!(result.errors.is_empty());
     }
@@ -435,11 +631,265 @@ mod tests {
         let mut file = NamedTempFile::new().unwrap();
         file.write_all(handoff.as_bytes()).unwrap();
 
-        let result = validate_handoff(&file.path().to_path_buf()).unwrap();
+        let result = validate_handoff(&file.path().to_path_buf(), Path::new(".")).unwrap();
         assert!(!result.valid);
         assert!(result.errors.iter().any(|e| e.contains("task_id")));
     }
 
+    #[test]
+    fn test_validate_handoff_reports_malformed_fields() {
+        let handoff = r#"{
+            "task_id": "task-1",
+            "worker_id": "worker-1",
+            "status": "complete",
+            "findings": [],
+            "artifacts": [],
+            "open_questions": [],
+            "timestamp": "1234567890"
+        }"#;
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(handoff.as_bytes()).unwrap();
+
+        let result = validate_handoff(&file.path().to_path_buf(), Path::new(".")).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].starts_with("timestamp:"));
+    }
+
+    #[test]
+    fn test_validate_handoff_requires_list_fields() {
+        let handoff = r#"{
+            "task_id": "task-1",
+            "worker_id": "worker-1",
+            "findings": [],
+            "timestamp": 1234567890
+        }"#;
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(handoff.as_bytes()).unwrap();
+
+        let result = validate_handoff(&file.path().to_path_buf(), Path::new(".")).unwrap();
+        assert!(!result.valid);
+        assert!(result.errors.contains(&"artifacts: missing".to_string()));
+        assert!(result.errors.contains(&"open_questions: missing".to_string()));
+        assert_eq!(result.errors.iter().filter(|e| e.starts_with("status:")).count(), 1);
+    }
+
+    #[test]
+    fn test_validate_handoff_partial_requires_context() {
+        let handoff = r#"{
+            "task_id": "task-1",
+            "worker_id": "worker-1",
+            "status": "partial",
+            "findings": [],
+            "artifacts": [],
+            "open_questions": [],
+            "timestamp": 1234567890
+        }"#;
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(handoff.as_bytes()).unwrap();
+
+        let result = validate_handoff(&file.path().to_path_buf(), Path::new(".")).unwrap();
+        assert!(!result.valid);
+        assert!(result.errors.iter().any(|e| e.starts_with("Partial status")));
+    }
+
+    #[test]
+    fn test_validate_handoff_warns_on_missing_details() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".mission/findings")).unwrap();
+        fs::write(dir.path().join(".mission/findings/api.md"), "# API").unwrap();
+
+        let handoff = r#"{
+            "task_id": "task-1",
+            "worker_id": "worker-1",
+            "status": "complete",
+            "findings": [
+                {"finding_type": "discovery", "summary": "API shape", "details_path": ".mission/findings/api.md"},
+                {"finding_type": "concern", "summary": "Auth", "details_path": ".mission/findings/auth.md"}
+            ],
+            "artifacts": [],
+            "open_questions": [],
+            "timestamp": 1234567890
+        }"#;
+        let file = dir.path().join("handoff.json");
+        fs::write(&file, handoff).unwrap();
+
+        let result = validate_handoff(&file, dir.path()).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.warnings, vec!["Finding 1 details not found: .mission/findings/auth.md".to_string()]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_files_does_not_follow_symlinks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(dir.path().join("spec.md"), "A short spec.").unwrap();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("loop")).unwrap();
+
+        let mut paths = Vec::new();
+        collect_files(dir.path(), &mut paths).unwrap();
+        paths.sort();
+        assert_eq!(paths, vec![dir.path().join("loop"), dir.path().join("spec.md")]);
+    }
+
+    #[test]
+    fn test_validate_handoffs_in_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("phase-2")).unwrap();
+        let valid = r#"{"task_id": "task-1", "worker_id": "worker-1", "status": "complete",
+            "findings": [{"finding_type": "discovery", "summary": "Found it"}],
+            "artifacts": [], "open_questions": [], "context_for_successor": null, "timestamp": 1}"#;
+        fs::write(dir.path().join("a.json"), valid).unwrap();
+        fs::write(dir.path().join("phase-2/b.json"), "{not json").unwrap();
+        fs::write(dir.path().join("notes.md"), "ignored").unwrap();
+        fs::write(dir.path().join("phase-2/c.json"), b"\xff\xfe").unwrap();
+
+        let files = handoff_files_in_dir(dir.path()).unwrap();
+        assert_eq!(files, vec![
+            dir.path().join("a.json"),
+            dir.path().join("phase-2/b.json"),
+            dir.path().join("phase-2/c.json"),
+        ]);
+
+        // The unreadable file is reported without stopping the run
+        let results = validate_handoffs(&files, Path::new("."));
+        assert_eq!(results.len(), 3);
+        assert!(results[0].result.valid);
+        assert!(!results[1].result.valid);
+        assert!(results[1].file.ends_with("b.json"));
+        assert!(!results[2].result.valid);
+        assert!(results[2].result.errors[0].starts_with("Failed to read file"));
+
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json[1]["valid"], false);
+        assert!(json[1]["errors"].as_array().is_some_and(|e| !e.is_empty()));
+    }
+
+    #[test]
+    fn test_count_tokens_in_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("findings")).unwrap();
+        fs::write(dir.path().join("spec.md"), "A short spec.").unwrap();
+        fs::write(dir.path().join("findings/long.md"), "word ".repeat(50)).unwrap();
+        fs::write(dir.path().join("state.json"), "{}").unwrap();
+
+        let result = count_tokens_in_dir(dir.path(), Some("*.md")).unwrap();
+        assert_eq!(result.files.len(), 2);
+        assert_eq!(result.files[0].path, Path::new("findings").join("long.md").display().to_string());
+        assert!(result.files[0].tokens > result.files[1].tokens);
+        assert_eq!(result.total, result.files[0].tokens + result.files[1].tokens);
+
+        let all = count_tokens_in_dir(dir.path(), None).unwrap();
+        assert_eq!(all.files.len(), 3);
+
+        let braces = count_tokens_in_dir(dir.path(), Some("*.{md,json}")).unwrap();
+        assert_eq!(braces.files.len(), 3);
+        assert!(count_tokens_in_dir(dir.path(), Some("[")).is_err());
+    }
+
+    #[test]
+    fn test_compile_checkpoint_with_max_tokens() {
+        let decisions: Vec<String> = (0..40).map(|i| format!("Decision number {} about the API", i)).collect();
+        let checkpoint = Checkpoint::new("cp-1", Stage::Design).with_decisions(decisions);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("checkpoint.json");
+        fs::write(&file, serde_json::to_string(&checkpoint).unwrap()).unwrap();
+
+        let full = compile_checkpoint(&file, None).unwrap();
+        assert_eq!(full.dropped_tokens, 0);
+
+        let trimmed = compile_checkpoint(&file, Some(50)).unwrap();
+        assert!(trimmed.tokens <= 50);
+        assert_eq!(trimmed.tokens + trimmed.dropped_tokens, full.tokens);
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("briefing.md");
+
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert!(!dir.path().join("briefing.md.tmp").exists());
+    }
+
+    fn write_gates(dir: &Path, json: &str) {
+        let state = dir.join("state");
+        fs::create_dir_all(&state).unwrap();
+        fs::write(state.join("gates.json"), json).unwrap();
+    }
+
+    /// gates.json entries approving every stage before `stage`.
+    fn approved_before(stage: Stage) -> String {
+        Stage::all()
+            .iter()
+            .take_while(|s| **s != stage)
+            .map(|s| format!(r#""{}": {{"status": "approved", "approved_at": "2026-02-10T12:30:45Z"}}, "#, s.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_check_gate_reads_structured_criteria() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_gates(dir.path(), &format!(r#"{{"gates": {{{}"design": {{"criteria": [
+            {{"description": "Spec document complete", "satisfied": true}},
+            {{"description": "Technical approach approved", "satisfied": true}}
+        ]}}}}}}"#, approved_before(Stage::Design)));
+
+        let result = check_gate("design", dir.path()).unwrap();
+        assert_eq!(result.status, "awaiting_approval");
+        assert!(result.criteria.iter().all(|c| c.satisfied));
+        assert!(result.can_approve);
+        assert!(result.blocking_reasons.is_empty());
+    }
+
+    #[test]
+    fn test_check_gate_requires_previous_gate() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_gates(dir.path(), r#"{"gates": {"design": {"criteria": [
+            {"description": "Spec document complete", "satisfied": true},
+            {"description": "Technical approach approved", "satisfied": true}
+        ]}}}"#);
+
+        let result = check_gate("design", dir.path()).unwrap();
+        assert!(!result.can_approve);
+        assert_eq!(result.blocking_reasons, vec!["Previous stage gate not open: planning"]);
+    }
+
+    #[test]
+    fn test_check_gate_approved() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_gates(dir.path(), r#"{"gates": {"goal": {
+            "status": "approved",
+            "criteria": ["Goal statement defined"],
+            "approved_at": "2026-02-10T12:30:45Z"
+        }}}"#);
+
+        let result = check_gate("goal", dir.path()).unwrap();
+        assert_eq!(result.status, "open");
+        assert!(!result.can_approve);
+    }
+
+    #[test]
+    fn test_check_gate_ignores_persisted_structural_criteria() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_gates(dir.path(), &format!(r#"{{"gates": {{{}"implement": {{"criteria": [
+            {{"description": "All unit tests pass", "satisfied": true}},
+            {{"description": "{}", "satisfied": false}}
+        ]}}}}}}"#, approved_before(Stage::Implement), INTEGRATOR_REQUIREMENT));
+
+        // No implement tasks -> integrator requirement passes
+        let result = check_gate("implement", dir.path()).unwrap();
+        assert_eq!(result.criteria.len(), 1);
+        assert!(result.can_approve);
+    }
+
     #[test]
     fn test_count_tokens() {
         let content = "Hello world, this is a test.";
@@ -468,6 +918,21 @@ mod tests {
         assert!(result.valid);
     }
 
+    #[test]
+    fn test_validate_checkpoint_tasks_past_stage() {
+        let checkpoint = Checkpoint::new("cp-1", Stage::Design).with_tasks(vec![
+            Task::new("task-1", "Design API", Stage::Design, "backend", "architect"),
+            Task::new("task-2", "Ship it", Stage::Release, "backend", "developer"),
+        ]);
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(serde_json::to_string(&checkpoint).unwrap().as_bytes()).unwrap();
+
+        let result = validate_checkpoint(&file.path().to_path_buf()).unwrap();
+        assert!(!result.valid);
+        assert!(result.errors.iter().any(|e| e.contains("task-2")));
+    }
+
     #[test]
     fn test_validate_checkpoint_invalid() {
         let checkpoint = r#"{ "not": "a checkpoint" }"#;
@@ -503,4 +968,24 @@ mod tests {
         assert!(briefing.contains("Use Rust for core"));
         assert!(briefing.contains("CI failing"));
     }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_json_schema_describes_payloads() {
+        let schema = serde_json::to_value(json_schema(SchemaKind::Handoff)).unwrap();
+        assert_eq!(schema["title"], "Handoff");
+        let required: Vec<&str> = schema["required"].as_array().unwrap().iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        assert!(required.contains(&"task_id"));
+        assert!(!required.contains(&"hash"));
+
+        let schema = serde_json::to_value(json_schema(SchemaKind::Task)).unwrap();
+        assert!(schema["definitions"]["Stage"]["enum"].as_array().unwrap().contains(&"discovery".into()));
+
+        // Legacy string reasons validate too; neither structured field is required
+        let reason = &schema["definitions"]["BlockReason"]["anyOf"];
+        assert_eq!(reason[0]["type"], "string");
+        assert!(reason[1].get("required").is_none());
+    }
 }
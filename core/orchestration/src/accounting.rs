@@ -0,0 +1,53 @@
+use knowledge::{BudgetStatus, KnowledgeManager};
+
+use runtime::UnifiedEvent;
+
+/// Record the tokens a parsed event carries against `worker_id`'s budget
+/// and return the budget's status afterwards. Events without tokens leave
+/// the budget unchanged. `None` when the worker has no budget.
+///
+/// Lives here rather than on `KnowledgeManager` so neither the knowledge
+/// crate nor the runtime has to depend on the other.
+pub fn apply_event(
+    manager: &mut KnowledgeManager,
+    worker_id: &str,
+    event: &UnifiedEvent,
+) -> Option<BudgetStatus> {
+    if let Some(tokens) = event.tokens {
+        manager.record_usage(worker_id, tokens as usize);
+    }
+    manager.check_budget(worker_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runtime::StreamParser;
+
+    #[test]
+    fn test_apply_parsed_events_to_budget() {
+        let mut manager = KnowledgeManager::new();
+        manager.create_budget("worker-1", 1000);
+        let mut parser = StreamParser::new("worker-1");
+
+        let mut status = None;
+        for line in [
+            r#"{"type":"turn","number":1}"#,
+            r#"{"type":"thinking","content":"Plan","tokens":450}"#,
+            r#"{"type":"tool_result","content":"ok","tokens":150}"#,
+        ] {
+            for event in parser.parse_line(line) {
+                status = apply_event(&mut manager, "worker-1", &event);
+            }
+        }
+
+        // The session totals on session_end aren't counted again
+        for event in parser.flush() {
+            status = apply_event(&mut manager, "worker-1", &event);
+        }
+
+        assert_eq!(manager.get_budget("worker-1").unwrap().used, 600);
+        assert_eq!(status, Some(BudgetStatus::Warning { remaining: 400 }));
+        assert_eq!(apply_event(&mut manager, "untracked", &UnifiedEvent::new("output").with_tokens(5)), None);
+    }
+}
@@ -0,0 +1,5 @@
+mod supervisor;
+mod accounting;
+
+pub use supervisor::{safe_to_transition, workers_needing_intervention, InterventionReason};
+pub use accounting::apply_event;
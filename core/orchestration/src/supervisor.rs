@@ -0,0 +1,135 @@
+use knowledge::{BudgetStatus, KnowledgeManager};
+use workflow::WorkflowEngine;
+
+use runtime::{HealthMonitor, HealthStatus};
+
+/// Why a worker needs the supervisor's attention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterventionReason {
+    /// No activity past the stuck threshold.
+    Stuck { since_ms: u64 },
+    /// Active and healthy, but the token budget is critical or exceeded:
+    /// burning tokens without finishing.
+    SilentBurn { budget: BudgetStatus },
+}
+
+/// Workers the supervisor should act on, combining health and budget
+/// signals. Sorted by worker id.
+pub fn workers_needing_intervention(
+    monitor: &HealthMonitor,
+    manager: &KnowledgeManager,
+) -> Vec<(String, InterventionReason)> {
+    let mut flagged: Vec<(String, InterventionReason)> = monitor.get_all_health()
+        .into_iter()
+        .filter_map(|(worker_id, health)| {
+            let reason = match health {
+                HealthStatus::Stuck { since_ms, .. } => InterventionReason::Stuck { since_ms },
+                HealthStatus::Healthy => match manager.check_budget(worker_id)? {
+                    budget @ (BudgetStatus::Critical { .. } | BudgetStatus::Exceeded) => {
+                        InterventionReason::SilentBurn { budget }
+                    }
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            Some((worker_id.to_string(), reason))
+        })
+        .collect();
+
+    flagged.sort_by(|a, b| a.0.cmp(&b.0));
+    flagged
+}
+
+/// Check that no worker on a task in the engine's current stage is stuck
+/// or dead before advancing. Workers are matched to tasks by the id given
+/// to `HealthMonitor::set_worker_task`. On failure returns one message per
+/// offending worker, sorted by worker id.
+pub fn safe_to_transition(engine: &WorkflowEngine, monitor: &HealthMonitor) -> Result<(), Vec<String>> {
+    let stage = engine.current_stage();
+    let mut problems: Vec<(String, String)> = monitor.get_all_health()
+        .into_iter()
+        .filter_map(|(worker_id, health)| {
+            let task_id = monitor.get_worker(worker_id)?.task_id.as_deref()?;
+            if engine.get_task(task_id)?.stage != stage {
+                return None;
+            }
+            let state = match health {
+                HealthStatus::Stuck { .. } => "stuck",
+                HealthStatus::Dead => "dead",
+                _ => return None,
+            };
+            Some((worker_id.to_string(), format!("Worker {} on task {} is {}", worker_id, task_id, state)))
+        })
+        .collect();
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    problems.sort();
+    Err(problems.into_iter().map(|(_, message)| message).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use workflow::{Stage, Task};
+
+    #[test]
+    fn test_flags_healthy_workers_over_budget() {
+        let mut monitor = HealthMonitor::new();
+        let mut manager = KnowledgeManager::new();
+        for id in ["exceeded", "critical", "fine", "untracked"] {
+            monitor.register_worker(id);
+        }
+        manager.create_budget("exceeded", 1000);
+        manager.record_usage("exceeded", 1000);
+        manager.create_budget("critical", 1000);
+        manager.record_usage("critical", 900);
+        manager.create_budget("fine", 1000);
+        manager.record_usage("fine", 100);
+
+        let flagged = workers_needing_intervention(&monitor, &manager);
+        assert_eq!(flagged.len(), 2);
+        assert_eq!(flagged[0].0, "critical");
+        assert!(matches!(
+            flagged[0].1,
+            InterventionReason::SilentBurn { budget: BudgetStatus::Critical { .. } }
+        ));
+        assert_eq!(flagged[1], ("exceeded".to_string(), InterventionReason::SilentBurn { budget: BudgetStatus::Exceeded }));
+    }
+
+    #[test]
+    fn test_flags_stuck_workers() {
+        // Zero thresholds make every worker stuck immediately
+        let mut monitor = HealthMonitor::with_thresholds(0, 0);
+        monitor.register_worker("worker-1");
+
+        let flagged = workers_needing_intervention(&monitor, &KnowledgeManager::new());
+        assert_eq!(flagged.len(), 1);
+        assert!(matches!(flagged[0].1, InterventionReason::Stuck { .. }));
+    }
+
+    #[test]
+    fn test_safe_to_transition_blocks_on_stuck_current_stage_worker() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Research", Stage::Discovery, "system", "researcher"));
+        engine.create_task(Task::new("task-2", "Build", Stage::Implement, "backend", "developer"));
+
+        // Zero thresholds make every worker stuck immediately
+        let mut monitor = HealthMonitor::with_thresholds(0, 0);
+        monitor.register_worker("later-stage");
+        monitor.set_worker_task("later-stage", Some("task-2"));
+        monitor.register_worker("unassigned");
+        assert!(safe_to_transition(&engine, &monitor).is_ok());
+
+        monitor.register_worker("researcher-1");
+        monitor.set_worker_task("researcher-1", Some("task-1"));
+        assert_eq!(
+            safe_to_transition(&engine, &monitor),
+            Err(vec!["Worker researcher-1 on task task-1 is stuck".to_string()])
+        );
+
+        let healthy = HealthMonitor::new();
+        assert!(safe_to_transition(&engine, &healthy).is_ok());
+    }
+}
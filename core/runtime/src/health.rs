@@ -7,7 +7,13 @@ pub enum HealthStatus {
     #[default]
     Healthy,
     Idle { since_ms: u64 },
-    Stuck { since_ms: u64 },
+    /// `since_ms` is the silent time so far; `since_timestamp` is the
+    /// fixed Unix ms of the last activity, for rendering "stuck since".
+    Stuck {
+        since_ms: u64,
+        #[serde(default)]
+        since_timestamp: u64,
+    },
     Unresponsive,
     Dead,
 }
@@ -20,6 +26,13 @@ pub struct WorkerHealth {
     pub last_activity: u64,
     pub last_tool_call: Option<u64>,
     pub turns_since_progress: usize,
+    /// Exponential moving average of gaps between activity, in ms.
+    pub avg_gap_ms: Option<f64>,
+    /// Tokens reported via `mark_tokens`. Observational only; budgets are
+    /// enforced by the knowledge crate's `TokenBudget`.
+    pub tokens_consumed: u64,
+    /// Workflow task the worker is on, if set via `set_worker_task`.
+    pub task_id: Option<String>,
 }
 
 impl WorkerHealth {
@@ -30,9 +43,15 @@ impl WorkerHealth {
             last_activity: Self::now(),
             last_tool_call: None,
             turns_since_progress: 0,
+            avg_gap_ms: None,
+            tokens_consumed: 0,
+            task_id: None,
         }
     }
 
+    /// Weight given to the newest gap in the moving average.
+    const GAP_SMOOTHING: f64 = 0.3;
+
     fn now() -> u64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -40,13 +59,24 @@ impl WorkerHealth {
             .as_millis() as u64
     }
 
+    fn record_gap(&mut self, now: u64) {
+        let gap = now.saturating_sub(self.last_activity) as f64;
+        self.avg_gap_ms = Some(match self.avg_gap_ms {
+            Some(avg) => avg + Self::GAP_SMOOTHING * (gap - avg),
+            None => gap,
+        });
+    }
+
     pub fn mark_activity(&mut self) {
-        self.last_activity = Self::now();
+        let now = Self::now();
+        self.record_gap(now);
+        self.last_activity = now;
         self.status = HealthStatus::Healthy;
     }
 
     pub fn mark_tool_call(&mut self) {
         let now = Self::now();
+        self.record_gap(now);
         self.last_activity = now;
         self.last_tool_call = Some(now);
         self.turns_since_progress = 0;
@@ -57,6 +87,10 @@ impl WorkerHealth {
         self.turns_since_progress += 1;
     }
 
+    pub fn mark_tokens(&mut self, tokens: u64) {
+        self.tokens_consumed = self.tokens_consumed.saturating_add(tokens);
+    }
+
     pub fn time_since_activity(&self) -> u64 {
         Self::now().saturating_sub(self.last_activity)
     }
@@ -66,10 +100,18 @@ impl WorkerHealth {
     }
 }
 
+/// Multipliers applied to a worker's average activity gap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AdaptiveFactors {
+    idle: f64,
+    stuck: f64,
+}
+
 pub struct HealthMonitor {
     workers: HashMap<String, WorkerHealth>,
     stuck_threshold_ms: u64,
     idle_threshold_ms: u64,
+    adaptive: Option<AdaptiveFactors>,
 }
 
 impl HealthMonitor {
@@ -78,6 +120,7 @@ impl HealthMonitor {
             workers: HashMap::new(),
             stuck_threshold_ms: 60000,  // 60 seconds
             idle_threshold_ms: 30000,   // 30 seconds
+            adaptive: None,
         }
     }
 
@@ -86,6 +129,40 @@ impl HealthMonitor {
             workers: HashMap::new(),
             stuck_threshold_ms: stuck_ms,
             idle_threshold_ms: idle_ms,
+            adaptive: None,
+        }
+    }
+
+    /// Change the fixed thresholds, keeping registered workers.
+    pub fn set_thresholds(&mut self, stuck_ms: u64, idle_ms: u64) {
+        self.stuck_threshold_ms = stuck_ms;
+        self.idle_threshold_ms = idle_ms;
+    }
+
+    /// Fixed `(stuck_ms, idle_ms)` thresholds.
+    pub fn thresholds(&self) -> (u64, u64) {
+        (self.stuck_threshold_ms, self.idle_threshold_ms)
+    }
+
+    /// Scale idle/stuck thresholds to each worker's own rhythm: a worker is
+    /// idle once its current gap exceeds `idle_factor` times its average gap,
+    /// and stuck past `stuck_factor` times. The fixed thresholds remain the
+    /// floor, and apply alone until a worker has gap history.
+    pub fn with_adaptive_thresholds(mut self, idle_factor: f64, stuck_factor: f64) -> Self {
+        self.adaptive = Some(AdaptiveFactors {
+            idle: idle_factor,
+            stuck: stuck_factor,
+        });
+        self
+    }
+
+    fn thresholds_for(&self, health: &WorkerHealth) -> (u64, u64) {
+        match (self.adaptive, health.avg_gap_ms) {
+            (Some(factors), Some(avg)) => (
+                self.idle_threshold_ms.max((avg * factors.idle) as u64),
+                self.stuck_threshold_ms.max((avg * factors.stuck) as u64),
+            ),
+            _ => (self.idle_threshold_ms, self.stuck_threshold_ms),
         }
     }
 
@@ -100,6 +177,20 @@ impl HealthMonitor {
         self.workers.remove(worker_id);
     }
 
+    /// Unregister workers silent for longer than `max_age_ms`, e.g. ones
+    /// that crashed without unregistering. Returns the removed ids, sorted.
+    pub fn prune_dead(&mut self, max_age_ms: u64) -> Vec<String> {
+        let mut removed: Vec<String> = self.workers.iter()
+            .filter(|(_, health)| health.time_since_activity() > max_age_ms)
+            .map(|(id, _)| id.clone())
+            .collect();
+        removed.sort();
+        for id in &removed {
+            self.workers.remove(id);
+        }
+        removed
+    }
+
     pub fn mark_activity(&mut self, worker_id: &str) {
         if let Some(health) = self.workers.get_mut(worker_id) {
             health.mark_activity();
@@ -118,6 +209,20 @@ impl HealthMonitor {
         }
     }
 
+    /// Record which workflow task a worker is on, or `None` once it's done.
+    pub fn set_worker_task(&mut self, worker_id: &str, task_id: Option<&str>) {
+        if let Some(health) = self.workers.get_mut(worker_id) {
+            health.task_id = task_id.map(str::to_string);
+        }
+    }
+
+    /// Add to a worker's `tokens_consumed`. Doesn't count as activity.
+    pub fn mark_tokens(&mut self, worker_id: &str, tokens: u64) {
+        if let Some(health) = self.workers.get_mut(worker_id) {
+            health.mark_tokens(tokens);
+        }
+    }
+
     pub fn check_health(&self, worker_id: &str) -> Option<HealthStatus> {
         self.workers.get(worker_id).map(|health| {
             self.compute_status(health)
@@ -126,10 +231,11 @@ impl HealthMonitor {
 
     fn compute_status(&self, health: &WorkerHealth) -> HealthStatus {
         let idle_time = health.time_since_activity();
+        let (idle_threshold_ms, stuck_threshold_ms) = self.thresholds_for(health);
 
-        if idle_time >= self.stuck_threshold_ms {
-            HealthStatus::Stuck { since_ms: idle_time }
-        } else if idle_time >= self.idle_threshold_ms {
+        if idle_time >= stuck_threshold_ms {
+            HealthStatus::Stuck { since_ms: idle_time, since_timestamp: health.last_activity }
+        } else if idle_time >= idle_threshold_ms {
             HealthStatus::Idle { since_ms: idle_time }
         } else {
             HealthStatus::Healthy
@@ -139,7 +245,7 @@ impl HealthMonitor {
     pub fn get_stuck_workers(&self) -> Vec<&str> {
         self.workers.iter()
             .filter(|(_, health)| {
-                health.time_since_activity() >= self.stuck_threshold_ms
+                matches!(self.compute_status(health), HealthStatus::Stuck { .. })
             })
             .map(|(id, _)| id.as_str())
             .collect()
@@ -181,6 +287,22 @@ mod tests {
         assert_eq!(monitor.check_health("worker-1"), Some(HealthStatus::Healthy));
     }
 
+    #[test]
+    fn test_prune_dead() {
+        let mut monitor = HealthMonitor::new();
+        monitor.register_worker("worker-1");
+        monitor.register_worker("worker-2");
+        monitor.register_worker("worker-3");
+        for id in ["worker-1", "worker-3"] {
+            monitor.workers.get_mut(id).unwrap().last_activity -= 3_600_000;
+        }
+
+        assert_eq!(monitor.prune_dead(600_000), vec!["worker-1".to_string(), "worker-3".to_string()]);
+        assert!(monitor.check_health("worker-1").is_none());
+        assert!(monitor.check_health("worker-2").is_some());
+        assert!(monitor.prune_dead(600_000).is_empty());
+    }
+
     #[test]
     fn test_worker_unregistration() {
         let mut monitor = HealthMonitor::new();
@@ -210,11 +332,29 @@ mod tests {
         assert_eq!(health.turns_since_progress, 0);
     }
 
+    #[test]
+    fn test_token_marking() {
+        let mut monitor = HealthMonitor::new();
+        monitor.register_worker("worker-1");
+        monitor.mark_tokens("worker-1", 150);
+        monitor.mark_tokens("worker-1", 50);
+        monitor.mark_tokens("unknown", 10);
+
+        let health = monitor.get_worker("worker-1").unwrap();
+        assert_eq!(health.tokens_consumed, 200);
+        assert!(health.avg_gap_ms.is_none());
+    }
+
     #[test]
     fn test_custom_thresholds() {
-        let monitor = HealthMonitor::with_thresholds(5000, 2000);
+        let mut monitor = HealthMonitor::with_thresholds(5000, 2000);
         assert_eq!(monitor.stuck_threshold_ms, 5000);
         assert_eq!(monitor.idle_threshold_ms, 2000);
+
+        monitor.register_worker("worker-1");
+        monitor.set_thresholds(9000, 3000);
+        assert_eq!(monitor.thresholds(), (9000, 3000));
+        assert!(monitor.check_health("worker-1").is_some());
     }
 
     #[test]
@@ -226,4 +366,71 @@ mod tests {
         let all = monitor.get_all_health();
         assert_eq!(all.len(), 2);
     }
+
+    fn worker_with_history(avg_gap_ms: f64, idle_for_ms: u64) -> WorkerHealth {
+        let mut health = WorkerHealth::new("worker-1");
+        health.avg_gap_ms = Some(avg_gap_ms);
+        health.last_activity = WorkerHealth::now() - idle_for_ms;
+        health
+    }
+
+    #[test]
+    fn test_activity_updates_average_gap() {
+        let mut health = WorkerHealth::new("worker-1");
+        assert!(health.avg_gap_ms.is_none());
+
+        health.last_activity -= 1000;
+        health.mark_activity();
+        let first = health.avg_gap_ms.unwrap();
+        assert!(first >= 1000.0);
+
+        health.mark_tool_call();
+        assert!(health.avg_gap_ms.unwrap() < first);
+    }
+
+    #[test]
+    fn test_adaptive_thresholds_tolerate_bursty_worker() {
+        // Worker typically pauses ~40s; a 45s gap is normal for it
+        let health = worker_with_history(40000.0, 45000);
+
+        let flat = HealthMonitor::new();
+        assert!(matches!(flat.compute_status(&health), HealthStatus::Idle { .. }));
+
+        let adaptive = HealthMonitor::new().with_adaptive_thresholds(2.0, 4.0);
+        assert_eq!(adaptive.compute_status(&health), HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_adaptive_thresholds_flag_long_gaps() {
+        let adaptive = HealthMonitor::new().with_adaptive_thresholds(2.0, 4.0);
+
+        let idle = worker_with_history(20000.0, 45000);
+        assert!(matches!(adaptive.compute_status(&idle), HealthStatus::Idle { .. }));
+
+        let stuck = worker_with_history(20000.0, 85000);
+        assert!(matches!(adaptive.compute_status(&stuck), HealthStatus::Stuck { .. }));
+    }
+
+    #[test]
+    fn test_adaptive_thresholds_keep_fixed_floor() {
+        // Fast worker with a 1s average gap is not idle after 5s
+        let adaptive = HealthMonitor::new().with_adaptive_thresholds(2.0, 4.0);
+        let health = worker_with_history(1000.0, 5000);
+        assert_eq!(adaptive.compute_status(&health), HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_stuck_reports_fixed_timestamp() {
+        let mut monitor = HealthMonitor::with_thresholds(0, 0);
+        monitor.register_worker("worker-1");
+        let last_activity = monitor.get_worker("worker-1").unwrap().last_activity;
+
+        match monitor.check_health("worker-1") {
+            Some(HealthStatus::Stuck { since_ms, since_timestamp }) => {
+                assert_eq!(since_timestamp, last_activity);
+                assert!(since_timestamp + since_ms >= last_activity);
+            }
+            other => panic!("expected Stuck, got {:?}", other),
+        }
+    }
 }
@@ -1,5 +1,9 @@
 mod health;
 mod stream;
+mod multiplexer;
+mod reader;
 
 pub use health::{HealthMonitor, HealthStatus, WorkerHealth};
-pub use stream::{StreamParser, UnifiedEvent, AgentFormat};
+pub use stream::{StreamParser, UnifiedEvent, AgentFormat, TimestampMode};
+pub use multiplexer::StreamMultiplexer;
+pub use reader::parse_reader;
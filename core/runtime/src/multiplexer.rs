@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use crate::stream::{StreamParser, TimestampMode, UnifiedEvent};
+
+/// Routes interleaved output from several agents to one `StreamParser`
+/// per agent, creating parsers on first sight of an agent id.
+#[derive(Default)]
+pub struct StreamMultiplexer {
+    parsers: HashMap<String, StreamParser>,
+    timestamp_mode: TimestampMode,
+}
+
+impl StreamMultiplexer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Timestamp mode for parsers created from now on.
+    pub fn with_timestamp_mode(mut self, mode: TimestampMode) -> Self {
+        self.timestamp_mode = mode;
+        self
+    }
+
+    /// Parse a line of output from `agent_id`
+    pub fn parse_line(&mut self, agent_id: &str, line: &str) -> Vec<UnifiedEvent> {
+        let mode = self.timestamp_mode;
+        self.parsers
+            .entry(agent_id.to_string())
+            .or_insert_with(|| StreamParser::new(agent_id).with_timestamp_mode(mode))
+            .parse_line(line)
+    }
+
+    pub fn get_parser(&self, agent_id: &str) -> Option<&StreamParser> {
+        self.parsers.get(agent_id)
+    }
+
+    /// Drop the parser for an agent, e.g. once it has exited
+    pub fn remove(&mut self, agent_id: &str) -> Option<StreamParser> {
+        self.parsers.remove(agent_id)
+    }
+
+    pub fn agent_ids(&self) -> Vec<&str> {
+        self.parsers.keys().map(|id| id.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_lines_per_agent() {
+        let mut mux = StreamMultiplexer::new();
+        assert!(mux.get_parser("a").is_none());
+
+        let events = mux.parse_line("a", "[Turn 3]");
+        assert_eq!(events[0].agent_id.as_deref(), Some("a"));
+        mux.parse_line("b", "[Turn 1]");
+
+        assert_eq!(mux.get_parser("a").unwrap().current_turn(), 3);
+        assert_eq!(mux.get_parser("b").unwrap().current_turn(), 1);
+        assert_eq!(mux.agent_ids().len(), 2);
+
+        assert!(mux.remove("a").is_some());
+        assert!(mux.get_parser("a").is_none());
+    }
+}
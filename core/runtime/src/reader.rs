@@ -0,0 +1,99 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use crate::stream::{StreamParser, UnifiedEvent};
+
+/// Feed every line of `reader` (e.g. an agent subprocess's stdout) through
+/// `parser` and yield the resulting events in order. Once the reader is
+/// exhausted the parser is flushed, so the last event is always the
+/// `session_end` carrying the session totals. Invalid UTF-8 is replaced
+/// rather than ending the stream. A read error is reported as an `error`
+/// event and ends the stream the same way.
+pub fn parse_reader<R: BufRead>(
+    reader: R,
+    mut parser: StreamParser,
+) -> impl Iterator<Item = UnifiedEvent> {
+    let mut source = Some(reader);
+    let mut buf = Vec::new();
+    let mut pending = VecDeque::new();
+
+    std::iter::from_fn(move || loop {
+        if let Some(event) = pending.pop_front() {
+            return Some(event);
+        }
+        let reader = source.as_mut()?;
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => {
+                source = None;
+                pending.extend(parser.flush());
+            }
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf);
+                let line = line.strip_suffix('\n').unwrap_or(&line);
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                pending.extend(parser.parse_line(line));
+            }
+            Err(e) => {
+                source = None;
+                pending.push_back(UnifiedEvent::new("error").with_error(e.to_string()));
+                pending.extend(parser.flush());
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, BufReader, Cursor, Read};
+
+    #[test]
+    fn test_parse_reader_flushes_at_end() {
+        let output = "[Turn 1]\n\n{\"type\":\"tool_call\",\"tool\":\"Read\",\"args\":{}}\n[Turn 2]\n";
+        let events: Vec<_> = parse_reader(Cursor::new(output), StreamParser::new("agent-1")).collect();
+
+        let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["turn", "tool_call", "turn", "session_end"]);
+        let end = events.last().unwrap();
+        assert_eq!(end.turn, Some(2));
+        assert_eq!(end.tool_calls, Some(1));
+    }
+
+    #[test]
+    fn test_parse_reader_empty_input() {
+        let events: Vec<_> = parse_reader(Cursor::new(""), StreamParser::new("agent-1")).collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "session_end");
+    }
+
+    #[test]
+    fn test_parse_reader_survives_invalid_utf8() {
+        let mut output = b"[Turn 1]\r\nbad \xff byte\n".to_vec();
+        output.extend_from_slice(b"[Turn 2]\n");
+        let events: Vec<_> = parse_reader(Cursor::new(output), StreamParser::new("agent-1")).collect();
+
+        let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["turn", "output", "turn", "session_end"]);
+        assert_eq!(events[1].content.as_deref(), Some("bad \u{fffd} byte"));
+        assert_eq!(events.last().unwrap().turn, Some(2));
+    }
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed"))
+        }
+    }
+
+    #[test]
+    fn test_parse_reader_read_error() {
+        let reader = BufReader::new(Cursor::new("[Turn 1]\n").chain(FailingReader));
+        let events: Vec<_> = parse_reader(reader, StreamParser::new("agent-1")).collect();
+
+        let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["turn", "error", "session_end"]);
+        assert_eq!(events[1].error.as_deref(), Some("pipe closed"));
+    }
+}
@@ -1,9 +1,13 @@
 use serde::Serialize;
 use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 /// Unified event format for the orchestrator and UI
 #[derive(Debug, Clone, Serialize)]
 pub struct UnifiedEvent {
+    /// Text events come in three kinds: `assistant` (the model's visible
+    /// answer), `thinking` (its reasoning) and `output` (plain stdout lines).
     #[serde(rename = "type")]
     pub event_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -20,10 +24,23 @@ pub struct UnifiedEvent {
     pub turn: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tokens: Option<u32>,
+    /// Language of a fenced code block, on events built from one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Tool calls in the session, on `session_end`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<u32>,
+    /// Tokens across the session, on `session_end`. Kept out of `tokens`
+    /// so per-event accounting doesn't count the session twice.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Emit time in milliseconds, see `TimestampMode`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_ms: Option<u64>,
 }
 
 impl UnifiedEvent {
@@ -37,8 +54,12 @@ impl UnifiedEvent {
             result: None,
             turn: None,
             tokens: None,
+            language: None,
+            tool_calls: None,
+            total_tokens: None,
             status: None,
             error: None,
+            timestamp_ms: None,
         }
     }
 
@@ -77,8 +98,42 @@ impl UnifiedEvent {
         self.error = Some(error.into());
         self
     }
+
+    pub fn with_timestamp_ms(mut self, timestamp_ms: u64) -> Self {
+        self.timestamp_ms = Some(timestamp_ms);
+        self
+    }
 }
 
+/// Clock used to stamp `UnifiedEvent::timestamp_ms`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampMode {
+    /// Milliseconds since the Unix epoch
+    #[default]
+    WallClock,
+    /// Milliseconds since a process-wide anchor; never goes backwards,
+    /// comparable between parsers in the same process only
+    Monotonic,
+}
+
+impl TimestampMode {
+    fn now_ms(self) -> u64 {
+        match self {
+            TimestampMode::WallClock => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            TimestampMode::Monotonic => {
+                static ANCHOR: OnceLock<Instant> = OnceLock::new();
+                ANCHOR.get_or_init(Instant::now).elapsed().as_millis() as u64
+            }
+        }
+    }
+}
+
+/// Lines an open code fence buffers before they are emitted regardless.
+const MAX_FENCE_LINES: usize = 1000;
+
 /// Agent output format type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AgentFormat {
@@ -87,11 +142,61 @@ pub enum AgentFormat {
     Unknown,
 }
 
+impl AgentFormat {
+    /// Guess the format of a stream from a sample of its lines by majority
+    /// vote over the lines whose format is recognizable. Non-JSON lines are
+    /// ignored; a tie or an empty sample gives `Unknown`.
+    pub fn detect(sample_lines: &[&str]) -> AgentFormat {
+        let (mut python, mut claude) = (0, 0);
+        for line in sample_lines {
+            if let Ok(json) = serde_json::from_str::<Value>(line.trim()) {
+                match Self::from_json(&json) {
+                    AgentFormat::Python => python += 1,
+                    AgentFormat::ClaudeCode => claude += 1,
+                    AgentFormat::Unknown => {}
+                }
+            }
+        }
+
+        match python.cmp(&claude) {
+            std::cmp::Ordering::Greater => AgentFormat::Python,
+            std::cmp::Ordering::Less => AgentFormat::ClaudeCode,
+            std::cmp::Ordering::Equal => AgentFormat::Unknown,
+        }
+    }
+
+    /// The format a single JSON event looks like it came from.
+    fn from_json(json: &Value) -> AgentFormat {
+        if let Some(obj) = json.as_object() {
+            if let Some(type_val) = obj.get("type").and_then(|v| v.as_str()) {
+                match type_val {
+                    "assistant" | "user" | "result" | "system" => return AgentFormat::ClaudeCode,
+                    "turn" | "thinking" | "tool_call" | "tool_result" => return AgentFormat::Python,
+                    _ => {}
+                }
+            }
+
+            if obj.contains_key("message") {
+                return AgentFormat::ClaudeCode;
+            }
+        }
+        AgentFormat::Unknown
+    }
+}
+
 /// Stream parser for agent output
 pub struct StreamParser {
     format: AgentFormat,
     agent_id: String,
     current_turn: u32,
+    timestamp_mode: TimestampMode,
+    /// Session totals reported by `flush`.
+    total_tokens: u32,
+    total_tool_calls: u32,
+    /// Open fenced code block in text mode: its language and lines so far.
+    fence: Option<(String, Vec<String>)>,
+    /// Report undetectable JSON instead of guessing its format.
+    strict_format: bool,
 }
 
 impl StreamParser {
@@ -100,6 +205,11 @@ impl StreamParser {
             format: AgentFormat::Unknown,
             agent_id: agent_id.into(),
             current_turn: 0,
+            timestamp_mode: TimestampMode::default(),
+            total_tokens: 0,
+            total_tool_calls: 0,
+            fence: None,
+            strict_format: false,
         }
     }
 
@@ -108,24 +218,87 @@ impl StreamParser {
         self
     }
 
+    /// Emit an `error` event for JSON whose format can't be detected
+    /// instead of trying each parser in turn. Off by default.
+    pub fn with_strict_format(mut self) -> Self {
+        self.strict_format = true;
+        self
+    }
+
+    pub fn with_timestamp_mode(mut self, mode: TimestampMode) -> Self {
+        self.timestamp_mode = mode;
+        self
+    }
+
     pub fn current_turn(&self) -> u32 {
         self.current_turn
     }
 
+    /// Clear per-session state so the parser can be reused for a new
+    /// session of the same agent. Keeps `agent_id`; format is re-detected.
+    /// An unclosed code fence is discarded; `flush` first to keep it.
+    pub fn reset(&mut self) {
+        self.format = AgentFormat::Unknown;
+        self.current_turn = 0;
+        self.total_tokens = 0;
+        self.total_tool_calls = 0;
+        self.fence = None;
+    }
+
+    /// Begin a session: resets per-session state and returns the
+    /// `session_start` event that opens it.
+    pub fn start_session(&mut self) -> UnifiedEvent {
+        self.reset();
+        UnifiedEvent::new("session_start")
+            .with_agent_id(&self.agent_id)
+            .with_timestamp_ms(self.timestamp_mode.now_ms())
+    }
+
+    /// End the session. Returns the lines of a still-open code fence as an
+    /// `output` event, if any, followed by a `session_end` event carrying
+    /// the totals: turns in `turn`, plus `total_tokens` and `tool_calls`.
+    pub fn flush(&mut self) -> Vec<UnifiedEvent> {
+        let now = self.timestamp_mode.now_ms();
+        let mut events = Vec::new();
+        if let Some((language, lines)) = self.fence.take() {
+            events.push(self.fence_output(language, lines).with_timestamp_ms(now));
+        }
+
+        let mut end = UnifiedEvent::new("session_end")
+            .with_agent_id(&self.agent_id)
+            .with_turn(self.current_turn)
+            .with_timestamp_ms(now);
+        end.tool_calls = Some(self.total_tool_calls);
+        end.total_tokens = Some(self.total_tokens);
+        events.push(end);
+        events
+    }
+
     /// Parse a line and return unified events
     pub fn parse_line(&mut self, line: &str) -> Vec<UnifiedEvent> {
         let trimmed = line.trim();
-        if trimmed.is_empty() {
+        let mut events = if self.fence.is_some() {
+            // Inside a code fence lines are kept verbatim, blank or not
+            self.parse_fenced_line(line.trim_end_matches(['\r', '\n']))
+        } else if trimmed.is_empty() {
             return vec![];
+        } else {
+            // Try to parse as JSON, otherwise treat as plain text output
+            match serde_json::from_str::<Value>(trimmed) {
+                Ok(json) => self.parse_json(json),
+                Err(_) => self.parse_text(trimmed),
+            }
+        };
+
+        let now = self.timestamp_mode.now_ms();
+        for event in &mut events {
+            event.timestamp_ms.get_or_insert(now);
+            self.total_tokens = self.total_tokens.saturating_add(event.tokens.unwrap_or(0));
+            if event.event_type == "tool_call" {
+                self.total_tool_calls = self.total_tool_calls.saturating_add(1);
+            }
         }
-
-        // Try to parse as JSON
-        if let Ok(json) = serde_json::from_str::<Value>(trimmed) {
-            return self.parse_json(json);
-        }
-
-        // Not JSON - treat as plain text output
-        self.parse_text(trimmed)
+        events
     }
 
     /// Parse JSON input
@@ -137,6 +310,11 @@ impl StreamParser {
         match self.format {
             AgentFormat::Python => self.parse_python_json(json),
             AgentFormat::ClaudeCode => self.parse_claude_json(json),
+            AgentFormat::Unknown if self.strict_format => vec![
+                UnifiedEvent::new("error")
+                    .with_agent_id(&self.agent_id)
+                    .with_error("unrecognized agent format"),
+            ],
             AgentFormat::Unknown => {
                 let events = self.parse_python_json(json.clone());
                 if !events.is_empty() {
@@ -148,25 +326,7 @@ impl StreamParser {
     }
 
     fn detect_format(&mut self, json: &Value) {
-        if let Some(obj) = json.as_object() {
-            if let Some(type_val) = obj.get("type").and_then(|v| v.as_str()) {
-                match type_val {
-                    "assistant" | "user" | "result" | "system" => {
-                        self.format = AgentFormat::ClaudeCode;
-                        return;
-                    }
-                    "turn" | "thinking" | "tool_call" | "tool_result" => {
-                        self.format = AgentFormat::Python;
-                        return;
-                    }
-                    _ => {}
-                }
-            }
-
-            if obj.contains_key("message") {
-                self.format = AgentFormat::ClaudeCode;
-            }
-        }
+        self.format = AgentFormat::from_json(json);
     }
 
     fn parse_python_json(&mut self, json: Value) -> Vec<UnifiedEvent> {
@@ -254,9 +414,15 @@ impl StreamParser {
                 }
                 "content_block_delta" => {
                     if let Some(delta) = obj.get("delta") {
-                        if let Some(text) = delta.get("text").and_then(|v| v.as_str()) {
+                        if let Some(thinking) = delta.get("thinking").and_then(|v| v.as_str()) {
                             events.push(
                                 UnifiedEvent::new("thinking")
+                                    .with_agent_id(&self.agent_id)
+                                    .with_content(thinking),
+                            );
+                        } else if let Some(text) = delta.get("text").and_then(|v| v.as_str()) {
+                            events.push(
+                                UnifiedEvent::new("assistant")
                                     .with_agent_id(&self.agent_id)
                                     .with_content(text),
                             );
@@ -313,6 +479,14 @@ impl StreamParser {
                     );
                 }
             }
+
+            // Content blocks keep their order and carry the turn of the
+            // message they belong to, so replays can rebuild the interleaving
+            if matches!(event_type, "assistant" | "content_block_start" | "content_block_delta") {
+                for event in &mut events {
+                    event.turn = Some(self.current_turn);
+                }
+            }
         }
 
         events
@@ -328,12 +502,21 @@ impl StreamParser {
                 "text" => {
                     if let Some(text) = obj.get("text").and_then(|v| v.as_str()) {
                         events.push(
-                            UnifiedEvent::new("thinking")
+                            UnifiedEvent::new("assistant")
                                 .with_agent_id(&self.agent_id)
                                 .with_content(text),
                         );
                     }
                 }
+                "thinking" => {
+                    if let Some(thinking) = obj.get("thinking").and_then(|v| v.as_str()) {
+                        events.push(
+                            UnifiedEvent::new("thinking")
+                                .with_agent_id(&self.agent_id)
+                                .with_content(thinking),
+                        );
+                    }
+                }
                 "tool_use" => {
                     if let Some(name) = obj.get("name").and_then(|v| v.as_str()) {
                         let input = obj.get("input").cloned().unwrap_or(Value::Null);
@@ -360,9 +543,63 @@ impl StreamParser {
         events
     }
 
+    /// Accumulate a line of an open code fence, emitting the whole block
+    /// once the closing fence arrives: a bash `tool_call` for shell
+    /// languages, otherwise an `output` event. A fence that reaches
+    /// `MAX_FENCE_LINES` without closing is emitted as `output` in chunks
+    /// of that size, so an unterminated fence can't buffer without bound.
+    fn parse_fenced_line(&mut self, line: &str) -> Vec<UnifiedEvent> {
+        if line.trim() != "```" {
+            let (language, lines) = match self.fence.as_mut() {
+                Some(fence) => fence,
+                None => return vec![],
+            };
+            lines.push(line.to_string());
+            if lines.len() < MAX_FENCE_LINES {
+                return vec![];
+            }
+            let (language, lines) = (language.clone(), std::mem::take(lines));
+            return vec![self.fence_output(language, lines)];
+        }
+
+        let (language, lines) = match self.fence.take() {
+            Some(fence) => fence,
+            None => return vec![],
+        };
+        let block = lines.join("\n");
+        let mut event = match language.as_str() {
+            "bash" | "sh" | "shell" | "console" => UnifiedEvent::new("tool_call")
+                .with_tool("bash", serde_json::json!({"command": block})),
+            _ => UnifiedEvent::new("output").with_content(block),
+        }
+        .with_agent_id(&self.agent_id);
+        if !language.is_empty() {
+            event.language = Some(language);
+        }
+        vec![event]
+    }
+
+    /// Lines of an unclosed fence as an `output` event, whatever the
+    /// language: an unterminated shell block isn't a complete command.
+    fn fence_output(&self, language: String, lines: Vec<String>) -> UnifiedEvent {
+        let mut event = UnifiedEvent::new("output")
+            .with_agent_id(&self.agent_id)
+            .with_content(lines.join("\n"));
+        if !language.is_empty() {
+            event.language = Some(language);
+        }
+        event
+    }
+
     fn parse_text(&mut self, text: &str) -> Vec<UnifiedEvent> {
         let mut events = vec![];
 
+        // Open a fenced code block like "```bash"; see `parse_fenced_line`
+        if let Some(language) = text.strip_prefix("```") {
+            self.fence = Some((language.trim().to_string(), Vec::new()));
+            return events;
+        }
+
         // Detect turn markers like "[Turn 1]"
         if text.starts_with("[Turn ") {
             if let Some(end) = text.find(']') {
@@ -435,6 +672,130 @@ mod tests {
         assert_eq!(events[0].tool, Some("bash".to_string()));
     }
 
+    #[test]
+    fn test_claude_mixed_content_keeps_order_and_turn() {
+        let mut parser = StreamParser::new("test");
+        parser.parse_line(r#"{"type":"message_start","message":{}}"#);
+        parser.parse_line(r#"{"type":"message_start","message":{}}"#);
+        let events = parser.parse_line(
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Let me look."},{"type":"tool_use","name":"Read","input":{"path":"a.rs"}},{"type":"text","text":"Found it."}]}}"#,
+        );
+        let kinds: Vec<&str> = events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(kinds, vec!["assistant", "tool_call", "assistant"]);
+        assert_eq!(events[0].content.as_deref(), Some("Let me look."));
+        assert_eq!(events[2].content.as_deref(), Some("Found it."));
+        assert!(events.iter().all(|e| e.turn == Some(2)));
+
+        let events = parser.parse_line(r#"{"type":"content_block_delta","delta":{"type":"text_delta","text":"More"}}"#);
+        assert_eq!(events[0].turn, Some(2));
+    }
+
+    #[test]
+    fn test_claude_text_vs_thinking() {
+        let mut parser = StreamParser::new("test");
+        let events = parser.parse_line(
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"Check the schema"},{"type":"text","text":"Done."}]}}"#,
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "thinking");
+        assert_eq!(events[0].content.as_deref(), Some("Check the schema"));
+        assert_eq!(events[1].event_type, "assistant");
+        assert_eq!(events[1].content.as_deref(), Some("Done."));
+
+        let events = parser.parse_line(r#"{"type":"content_block_delta","delta":{"type":"thinking_delta","thinking":"Hmm"}}"#);
+        assert_eq!(events[0].event_type, "thinking");
+        let events = parser.parse_line(r#"{"type":"content_block_delta","delta":{"type":"text_delta","text":"Hi"}}"#);
+        assert_eq!(events[0].event_type, "assistant");
+    }
+
+    #[test]
+    fn test_events_are_timestamped() {
+        let mut parser = StreamParser::new("test");
+        let events = parser.parse_line(r#"{"type":"turn","number":1}"#);
+        assert!(events[0].timestamp_ms.unwrap() > 1_600_000_000_000);
+
+        let mut parser = StreamParser::new("test").with_timestamp_mode(TimestampMode::Monotonic);
+        let first = parser.parse_line("[Turn 1]")[0].timestamp_ms.unwrap();
+        let second = parser.parse_line("[Turn 2]")[0].timestamp_ms.unwrap();
+        assert!(second >= first);
+
+        let json = serde_json::to_value(UnifiedEvent::new("turn")).unwrap();
+        assert!(json.get("timestamp_ms").is_none());
+    }
+
+    #[test]
+    fn test_fenced_code_blocks() {
+        let mut parser = StreamParser::new("test");
+        assert!(parser.parse_line("```bash").is_empty());
+        assert!(parser.parse_line("cd src").is_empty());
+        assert!(parser.parse_line("").is_empty());
+        assert!(parser.parse_line("  cargo test").is_empty());
+        let events = parser.parse_line("```");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "tool_call");
+        assert_eq!(events[0].tool.as_deref(), Some("bash"));
+        assert_eq!(events[0].args.as_ref().unwrap()["command"], "cd src\n\n  cargo test");
+        assert_eq!(events[0].language.as_deref(), Some("bash"));
+
+        parser.parse_line("```json");
+        parser.parse_line(r#"{"type":"turn","number":9}"#);
+        let events = parser.parse_line("```");
+        assert_eq!(events[0].event_type, "output");
+        assert_eq!(events[0].content.as_deref(), Some(r#"{"type":"turn","number":9}"#));
+        assert_eq!(parser.current_turn(), 0);
+
+        assert_eq!(parser.parse_line("after")[0].event_type, "output");
+    }
+
+    #[test]
+    fn test_session_start_and_end() {
+        let mut parser = StreamParser::new("test");
+        let start = parser.start_session();
+        assert_eq!(start.event_type, "session_start");
+        assert_eq!(start.agent_id.as_deref(), Some("test"));
+
+        parser.parse_line(r#"{"type":"turn","number":1}"#);
+        parser.parse_line(r#"{"type":"thinking","content":"Plan","tokens":40}"#);
+        parser.parse_line(r#"{"type":"tool_call","tool":"bash","args":{"command":"ls"}}"#);
+        parser.parse_line(r#"{"type":"tool_result","content":"ok","tokens":10}"#);
+        parser.parse_line(r#"{"type":"turn","number":2}"#);
+
+        let mut events = parser.flush();
+        assert_eq!(events.len(), 1);
+        let end = events.pop().unwrap();
+        assert_eq!(end.event_type, "session_end");
+        assert_eq!(end.turn, Some(2));
+        assert_eq!(end.tokens, None);
+        assert_eq!(end.total_tokens, Some(50));
+        assert_eq!(end.tool_calls, Some(1));
+
+        parser.start_session();
+        assert_eq!(parser.flush()[0].tool_calls, Some(0));
+    }
+
+    #[test]
+    fn test_unclosed_fence_is_kept() {
+        let mut parser = StreamParser::new("test");
+        parser.parse_line("```bash");
+        parser.parse_line("cargo build");
+        let events = parser.flush();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "output");
+        assert_eq!(events[0].content.as_deref(), Some("cargo build"));
+        assert_eq!(events[0].language.as_deref(), Some("bash"));
+        assert_eq!(events[1].event_type, "session_end");
+
+        // Long unterminated fences are emitted in chunks
+        parser.parse_line("```");
+        for i in 0..MAX_FENCE_LINES {
+            let events = parser.parse_line(&format!("line {}", i));
+            assert_eq!(events.is_empty(), i + 1 < MAX_FENCE_LINES);
+        }
+        parser.parse_line("tail");
+        let events = parser.flush();
+        assert_eq!(events[0].content.as_deref(), Some("tail"));
+    }
+
     #[test]
     fn test_parse_text_turn() {
         let mut parser = StreamParser::new("test");
@@ -460,9 +821,56 @@ mod tests {
         assert!(events.is_empty());
     }
 
+    #[test]
+    fn test_detect_format_by_majority() {
+        let sample = [
+            r#"{"type":"system","subtype":"init"}"#,
+            "plain text banner",
+            r#"{"type":"turn","number":1}"#,
+            r#"{"type":"assistant","message":{"content":[]}}"#,
+            r#"{"type":"result","usage":{}}"#,
+        ];
+        assert_eq!(AgentFormat::detect(&sample), AgentFormat::ClaudeCode);
+        assert_eq!(AgentFormat::detect(&sample[1..3]), AgentFormat::Python);
+        assert_eq!(AgentFormat::detect(&sample[1..4]), AgentFormat::Unknown);
+        assert_eq!(AgentFormat::detect(&[]), AgentFormat::Unknown);
+    }
+
     #[test]
     fn test_with_format() {
         let parser = StreamParser::new("test").with_format(AgentFormat::Python);
         assert_eq!(parser.format, AgentFormat::Python);
     }
+
+    #[test]
+    fn test_strict_format_reports_unrecognized_json() {
+        let mut lenient = StreamParser::new("test");
+        let mut strict = StreamParser::new("test").with_strict_format();
+
+        let events = strict.parse_line(r#"{"event":"mystery"}"#);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "error");
+        assert_eq!(events[0].error.as_deref(), Some("unrecognized agent format"));
+
+        let events = strict.parse_line(r#"{"type":"tool_call","tool":"bash","args":{}}"#);
+        assert_eq!(events[0].event_type, "tool_call");
+        assert_eq!(strict.format, AgentFormat::Python);
+        assert!(lenient.parse_line(r#"{"event":"mystery"}"#).iter().all(|e| e.event_type != "error"));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut parser = StreamParser::new("test");
+        parser.parse_line(r#"{"type":"turn","number":3}"#);
+        assert_eq!(parser.format, AgentFormat::Python);
+        assert_eq!(parser.current_turn(), 3);
+
+        parser.reset();
+        assert_eq!(parser.format, AgentFormat::Unknown);
+        assert_eq!(parser.current_turn(), 0);
+        assert_eq!(parser.agent_id, "test");
+
+        parser.parse_line(r#"{"type":"system"}"#);
+        assert_eq!(parser.format, AgentFormat::ClaudeCode);
+    }
 }
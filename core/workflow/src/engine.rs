@@ -1,10 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::stage::Stage;
-use crate::task::{Task, TaskStatus};
-use crate::gate::{Gate, GateStatus};
+use crate::lifecycle::TaskStateMachine;
+use crate::task::{BlockCategory, Task, TaskStatus};
+use crate::gate::{ApprovalResponse, Gate, GateStatus, INTEGRATOR_REQUIREMENT, REVIEWER_REQUIREMENT};
 
 #[derive(Debug, Error)]
 pub enum WorkflowError {
@@ -25,13 +26,209 @@ pub enum WorkflowError {
 
     #[error("Invalid task status transition")]
     InvalidStatusTransition,
+
+    #[error("Task already exists: {0}")]
+    TaskAlreadyExists(String),
+
+    #[error("Approver name must not be empty")]
+    InvalidApprover,
+
+    #[error("Stage has incomplete tasks: {0:?}")]
+    StageTasksIncomplete(Vec<String>),
+
+    #[error("Zone already has a task in progress: {0}")]
+    ZoneBusy(String),
+
+    #[error("Schema version {found} is newer than supported version {supported}")]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+
+    #[error("Already at stage: {0:?}")]
+    AlreadyAtStage(Stage),
+
+    #[error("Approval response does not match the gate's current request: {0}")]
+    StaleApproval(String),
+}
+
+impl WorkflowError {
+    /// Stable machine-readable code, independent of the Display message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            WorkflowError::TaskNotFound(_) => "task_not_found",
+            WorkflowError::GateNotFound(_) => "gate_not_found",
+            WorkflowError::InvalidTransition { .. } => "invalid_transition",
+            WorkflowError::GateNotOpen(_) => "gate_not_open",
+            WorkflowError::SerializationError(_) => "serialization_error",
+            WorkflowError::InvalidStatusTransition => "invalid_status_transition",
+            WorkflowError::TaskAlreadyExists(_) => "task_already_exists",
+            WorkflowError::InvalidApprover => "invalid_approver",
+            WorkflowError::StageTasksIncomplete(_) => "stage_tasks_incomplete",
+            WorkflowError::ZoneBusy(_) => "zone_busy",
+            WorkflowError::UnsupportedSchemaVersion { .. } => "unsupported_schema_version",
+            WorkflowError::AlreadyAtStage(_) => "already_at_stage",
+            WorkflowError::StaleApproval(_) => "stale_approval",
+        }
+    }
+}
+
+/// A gate status or criteria-progress change observed by
+/// `WorkflowEngine::poll_gate_changes`. `from` equals `to` when only the
+/// number of satisfied criteria moved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GateChange {
+    pub stage: Stage,
+    pub from: GateStatus,
+    pub to: GateStatus,
+    /// Criteria satisfied and total after the change, as `Gate::progress`.
+    pub progress: (usize, usize),
+}
+
+/// A backward move recorded by `WorkflowEngine::reopen_stage`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageReopen {
+    pub from: Stage,
+    pub to: Stage,
+    /// Ids of the gates that were reset, in pipeline order.
+    pub reset_gates: Vec<String>,
+    pub reopened_at: u64,
+}
+
+/// Result of a successful `WorkflowEngine::transition`.
+///
+/// The engine can't reach the knowledge manager, so it only signals that
+/// `from` just completed. Callers act on the hint, typically with
+/// `KnowledgeManager::checkpoint_on_transition(&outcome, &tasks)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionOutcome {
+    pub from: Stage,
+    pub to: Stage,
+    /// A checkpoint of `from` should be captured now.
+    pub snapshot_recommended: bool,
+}
+
+/// What auto-advance did after a gate approval; see
+/// `WorkflowEngine::set_auto_advance`.
+#[derive(Debug)]
+pub enum AutoAdvance {
+    /// Auto-advance is off, the gate isn't the current stage's, or its
+    /// criteria aren't all met.
+    NotAttempted,
+    Advanced(TransitionOutcome),
+    /// The transition was attempted and refused, e.g. by strict transitions.
+    Blocked(WorkflowError),
+}
+
+/// Dispatch order for `(effective priority, task)` pairs: highest priority
+/// first, then oldest, then lowest id.
+fn dispatch_order((pa, a): (i32, &Task), (pb, b): (i32, &Task)) -> std::cmp::Ordering {
+    pb.cmp(&pa)
+        .then(a.created_at.cmp(&b.created_at))
+        .then_with(|| a.id.cmp(&b.id))
 }
 
+/// Schema version of serialized engine state written by this build.
+/// State without a version predates versioning and reads as 0.
+pub const WORKFLOW_SCHEMA_VERSION: u32 = 2;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowEngine {
+    #[serde(default)]
+    schema_version: u32,
     current_stage: Stage,
+    /// Serialized as an array sorted by id so saved state diffs cleanly.
+    #[serde(serialize_with = "serialize_by_id", deserialize_with = "deserialize_by_id")]
     tasks: HashMap<String, Task>,
+    #[serde(serialize_with = "serialize_by_id", deserialize_with = "deserialize_by_id")]
     gates: HashMap<String, Gate>,
+    /// Gate statuses as of the last `poll_gate_changes`, keyed by gate id.
+    #[serde(default, serialize_with = "serialize_sorted")]
+    seen_gate_statuses: HashMap<String, GateStatus>,
+    /// Gate criteria progress as of the last `poll_gate_changes`.
+    #[serde(default, serialize_with = "serialize_sorted")]
+    seen_gate_progress: HashMap<String, (usize, usize)>,
+    /// Counter for ids assigned by `create_task_auto`.
+    #[serde(default)]
+    task_counter: u64,
+    /// When set, `transition` also requires every task in the current
+    /// stage to be done or cancelled.
+    #[serde(default)]
+    strict_transitions: bool,
+    /// When set, `assign_task` refuses to start a task while another task
+    /// in the same zone is in progress.
+    #[serde(default)]
+    exclusive_zones: bool,
+    /// When set, approving the current stage's gate with its criteria met
+    /// also transitions to the next stage.
+    #[serde(default)]
+    auto_advance: bool,
+    /// Whether `transition` recommends a checkpoint of the completed stage.
+    #[serde(default = "default_snapshot_on_transition")]
+    snapshot_on_transition: bool,
+    /// Per-stage statuses that count as finished for gate purposes.
+    #[serde(default, serialize_with = "serialize_sorted")]
+    terminal_statuses: HashMap<Stage, Vec<TaskStatus>>,
+    /// Every `reopen_stage`, oldest first.
+    #[serde(default)]
+    reopen_history: Vec<StageReopen>,
+}
+
+fn default_snapshot_on_transition() -> bool {
+    true
+}
+
+/// Values stored in the engine keyed by their own id.
+trait Identified {
+    fn id(&self) -> &str;
+}
+
+impl Identified for Task {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Identified for Gate {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+fn serialize_by_id<T, S>(map: &HashMap<String, T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: serde::Serializer,
+{
+    let sorted: std::collections::BTreeMap<&String, &T> = map.iter().collect();
+    serializer.collect_seq(sorted.values())
+}
+
+/// Accepts the id-sorted array written since schema version 2, or the
+/// id-keyed map written before it.
+fn deserialize_by_id<'de, T, D>(deserializer: D) -> Result<HashMap<String, T>, D::Error>
+where
+    T: Deserialize<'de> + Identified,
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr<T> {
+        List(Vec<T>),
+        Map(HashMap<String, T>),
+    }
+
+    Ok(match Repr::deserialize(deserializer)? {
+        Repr::List(values) => values.into_iter().map(|v: T| (v.id().to_string(), v)).collect(),
+        Repr::Map(map) => map,
+    })
+}
+
+fn serialize_sorted<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize + Ord,
+    V: Serialize,
+    S: serde::Serializer,
+{
+    let sorted: std::collections::BTreeMap<&K, &V> = map.iter().collect();
+    sorted.serialize(serializer)
 }
 
 impl WorkflowEngine {
@@ -43,9 +240,55 @@ impl WorkflowEngine {
         }
 
         Self {
+            schema_version: WORKFLOW_SCHEMA_VERSION,
             current_stage: Stage::Discovery,
             tasks: HashMap::new(),
             gates,
+            seen_gate_statuses: HashMap::new(),
+            seen_gate_progress: HashMap::new(),
+            task_counter: 0,
+            strict_transitions: false,
+            exclusive_zones: false,
+            auto_advance: false,
+            snapshot_on_transition: true,
+            terminal_statuses: HashMap::new(),
+            reopen_history: Vec::new(),
+        }
+    }
+
+    pub fn set_strict_transitions(&mut self, strict: bool) {
+        self.strict_transitions = strict;
+    }
+
+    pub fn set_exclusive_zones(&mut self, exclusive: bool) {
+        self.exclusive_zones = exclusive;
+    }
+
+    pub fn set_auto_advance(&mut self, enabled: bool) {
+        self.auto_advance = enabled;
+    }
+
+    /// Enabled by default.
+    pub fn set_snapshot_on_transition(&mut self, enabled: bool) {
+        self.snapshot_on_transition = enabled;
+    }
+
+    /// Statuses that count as finished for tasks in `stage` when checking
+    /// gates and strict transitions. Block reasons are ignored. Without a
+    /// setting, strict transitions accept done or cancelled and structural
+    /// gate checks require done.
+    pub fn set_terminal_statuses(&mut self, stage: Stage, statuses: Vec<TaskStatus>) {
+        self.terminal_statuses.insert(stage, statuses);
+        self.sync_zone_gates();
+    }
+
+    /// Whether `task` counts as finished for its stage, falling back to
+    /// `default` when the stage has no configured terminal statuses.
+    fn is_terminal_for_stage(&self, task: &Task, default: impl Fn(&Task) -> bool) -> bool {
+        match self.terminal_statuses.get(&task.stage) {
+            Some(statuses) => statuses.iter()
+                .any(|s| std::mem::discriminant(s) == std::mem::discriminant(&task.status)),
+            None => default(task),
         }
     }
 
@@ -65,7 +308,26 @@ impl WorkflowEngine {
         false
     }
 
-    pub fn transition(&mut self, to: Stage) -> Result<(), WorkflowError> {
+    /// Advance to `to`, which must be the next stage. Requesting the
+    /// current stage fails with `AlreadyAtStage` rather than
+    /// `InvalidTransition`.
+    pub fn transition(&mut self, to: Stage) -> Result<TransitionOutcome, WorkflowError> {
+        self.transition_dry_run(to)?;
+
+        let from = self.current_stage;
+        self.current_stage = to;
+        Ok(TransitionOutcome {
+            from,
+            to,
+            snapshot_recommended: self.snapshot_on_transition,
+        })
+    }
+
+    /// Run every check `transition` would, without changing state.
+    pub fn transition_dry_run(&self, to: Stage) -> Result<(), WorkflowError> {
+        if to == self.current_stage {
+            return Err(WorkflowError::AlreadyAtStage(to));
+        }
         if !self.can_transition(to) {
             if self.check_gate(self.current_stage) != GateStatus::Open {
                 return Err(WorkflowError::GateNotOpen(self.current_stage));
@@ -76,35 +338,306 @@ impl WorkflowEngine {
             });
         }
 
-        self.current_stage = to;
+        if self.strict_transitions {
+            let mut incomplete: Vec<String> = self.tasks.values()
+                .filter(|t| t.stage == self.current_stage)
+                .filter(|t| !self.is_terminal_for_stage(t, |t| matches!(t.status, TaskStatus::Done | TaskStatus::Cancelled)))
+                .map(|t| t.id.clone())
+                .collect();
+            if !incomplete.is_empty() {
+                incomplete.sort();
+                return Err(WorkflowError::StageTasksIncomplete(incomplete));
+            }
+        }
+
         Ok(())
     }
 
+    /// Move back to `stage`, which must not be after the current stage.
+    /// The gates of `stage` and every later stage are reset to `Closed`
+    /// with their approvals cleared, so re-advancing can't reuse stale
+    /// approvals. The move is recorded in `reopen_history`.
+    pub fn reopen_stage(&mut self, stage: Stage) -> Result<StageReopen, WorkflowError> {
+        if stage > self.current_stage {
+            return Err(WorkflowError::InvalidTransition {
+                from: self.current_stage,
+                to: stage,
+            });
+        }
+
+        let mut reset_gates = Vec::new();
+        for later in Stage::all().iter().copied().filter(|s| *s >= stage) {
+            if let Some(gate) = self.get_gate_mut(later) {
+                gate.reset();
+                reset_gates.push(gate.id.clone());
+            }
+        }
+        self.sync_zone_gates();
+
+        let from = self.current_stage;
+        self.current_stage = stage;
+        let reopen = StageReopen {
+            from,
+            to: stage,
+            reset_gates,
+            reopened_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        };
+        self.reopen_history.push(reopen.clone());
+        Ok(reopen)
+    }
+
+    pub fn reopen_history(&self) -> &[StageReopen] {
+        &self.reopen_history
+    }
+
     // Task management
+
+    /// Insert a task, replacing any existing task with the same id.
+    /// Use `try_create_task` when a duplicate id should be an error.
     pub fn create_task(&mut self, task: Task) -> String {
         let id = task.id.clone();
         self.tasks.insert(id.clone(), task);
+        self.sync_zone_gates();
         id
     }
 
-    pub fn update_task_status(&mut self, id: &str, status: TaskStatus) -> Result<(), WorkflowError> {
+    /// Insert a task only if its id is not already taken.
+    pub fn try_create_task(&mut self, task: Task) -> Result<String, WorkflowError> {
+        if self.tasks.contains_key(&task.id) {
+            return Err(WorkflowError::TaskAlreadyExists(task.id));
+        }
+        Ok(self.create_task(task))
+    }
+
+    /// Create a task with an engine-assigned id of the form
+    /// `task-{stage}-{n}`, skipping any ids already in use.
+    pub fn create_task_auto(
+        &mut self,
+        name: impl Into<String>,
+        stage: Stage,
+        zone: impl Into<String>,
+        persona: impl Into<String>,
+    ) -> String {
+        let id = loop {
+            self.task_counter += 1;
+            let candidate = format!("task-{}-{}", stage.as_str(), self.task_counter);
+            if !self.tasks.contains_key(&candidate) {
+                break candidate;
+            }
+        };
+
+        self.create_task(Task::new(id, name, stage, zone, persona))
+    }
+
+    /// Edit a stored task in place and refresh its `updated_at`. The id
+    /// can't be changed. A status change is checked and recorded as in
+    /// `update_task_status`; if it is illegal the old status is put back
+    /// and `InvalidStatusTransition` is returned. Other edits made by `f`
+    /// are kept either way.
+    pub fn update_task<F: FnOnce(&mut Task)>(&mut self, id: &str, f: F) -> Result<(), WorkflowError> {
         let task = self.tasks.get_mut(id)
             .ok_or_else(|| WorkflowError::TaskNotFound(id.to_string()))?;
 
-        task.status = status;
+        let old_status = task.status.clone();
+        f(task);
+        if task.id != id {
+            task.id = id.to_string();
+        }
+
+        let mut result = Ok(());
+        if task.status != old_status && !TaskStateMachine::can_transition(&old_status, &task.status) {
+            task.status = old_status.clone();
+            result = Err(WorkflowError::InvalidStatusTransition);
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        task.updated_at = now;
+        if task.status != old_status {
+            task.status_history.push((now, task.status.clone()));
+        }
+
+        self.sync_zone_gates();
+        result
+    }
+
+    /// Change a task's id, rewriting every dependency on it. Fails if
+    /// `old_id` is unknown or `new_id` is taken; renaming to the same id
+    /// is a no-op.
+    pub fn rename_task(&mut self, old_id: &str, new_id: &str) -> Result<(), WorkflowError> {
+        if !self.tasks.contains_key(old_id) {
+            return Err(WorkflowError::TaskNotFound(old_id.to_string()));
+        }
+        if old_id == new_id {
+            return Ok(());
+        }
+        if self.tasks.contains_key(new_id) {
+            return Err(WorkflowError::TaskAlreadyExists(new_id.to_string()));
+        }
+
+        let mut task = self.tasks.remove(old_id).unwrap();
+        task.id = new_id.to_string();
         task.updated_at = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
+        self.tasks.insert(new_id.to_string(), task);
+
+        for other in self.tasks.values_mut() {
+            for dep in other.dependencies.iter_mut().filter(|d| *d == old_id) {
+                *dep = new_id.to_string();
+            }
+        }
+        self.sync_zone_gates();
+        Ok(())
+    }
+
+    /// Remove `Done` and `Cancelled` tasks last updated before `before`,
+    /// keeping any that a non-terminal task still depends on. Returns the
+    /// removed tasks sorted by id so callers can archive them.
+    pub fn prune_completed(&mut self, before: u64) -> Vec<Task> {
+        let is_finished = |t: &Task| matches!(t.status, TaskStatus::Done | TaskStatus::Cancelled);
+        let still_needed: HashSet<&str> = self.tasks.values()
+            .filter(|t| !is_finished(t))
+            .flat_map(|t| t.dependencies.iter().map(|d| d.as_str()))
+            .collect();
+
+        let mut ids: Vec<String> = self.tasks.values()
+            .filter(|t| is_finished(t) && t.updated_at < before && !still_needed.contains(t.id.as_str()))
+            .map(|t| t.id.clone())
+            .collect();
+        ids.sort();
+
+        let pruned = ids.iter().filter_map(|id| self.tasks.remove(id)).collect();
+        self.sync_zone_gates();
+        pruned
+    }
+
+    /// Set a task's status, rejecting changes `TaskStateMachine` disallows.
+    /// Setting the status a task already has is a no-op, so retries succeed.
+    pub fn update_task_status(&mut self, id: &str, status: TaskStatus) -> Result<(), WorkflowError> {
+        let task = self.tasks.get_mut(id)
+            .ok_or_else(|| WorkflowError::TaskNotFound(id.to_string()))?;
+
+        if task.status == status {
+            return Ok(());
+        }
+        if !TaskStateMachine::can_transition(&task.status, &status) {
+            return Err(WorkflowError::InvalidStatusTransition);
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        task.status_history.push((now, status.clone()));
+        task.status = status;
+        task.updated_at = now;
 
+        self.sync_zone_gates();
         Ok(())
     }
 
+    /// Start a task. With exclusive zones enabled, fails with `ZoneBusy`
+    /// if another task in the same zone is already in progress.
+    pub fn assign_task(&mut self, id: &str) -> Result<(), WorkflowError> {
+        let task = self.tasks.get(id)
+            .ok_or_else(|| WorkflowError::TaskNotFound(id.to_string()))?;
+
+        if self.exclusive_zones && !self.conflicting_tasks(id).is_empty() {
+            return Err(WorkflowError::ZoneBusy(task.zone.clone()));
+        }
+
+        self.update_task_status(id, TaskStatus::InProgress)
+    }
+
+    /// Other in-progress tasks in the same zone as `task_id`.
+    pub fn conflicting_tasks(&self, task_id: &str) -> Vec<&Task> {
+        let zone = match self.tasks.get(task_id) {
+            Some(task) => &task.zone,
+            None => return Vec::new(),
+        };
+
+        self.tasks.values()
+            .filter(|t| t.id != task_id && &t.zone == zone && t.status == TaskStatus::InProgress)
+            .collect()
+    }
+
     pub fn get_task(&self, id: &str) -> Option<&Task> {
         self.tasks.get(id)
     }
 
+    /// Ready tasks, highest `effective_priority` first, then oldest, then
+    /// lowest id.
     pub fn get_ready_tasks(&self) -> Vec<&Task> {
+        let dependents = self.dependents_index();
+        let mut ready: Vec<(i32, &Task)> = self.iter_ready_tasks()
+            .map(|t| (self.effective_priority_with(&t.id, &dependents), t))
+            .collect();
+        ready.sort_by(|a, b| dispatch_order(*a, *b));
+        ready.into_iter().map(|(_, t)| t).collect()
+    }
+
+    /// The highest priority among a task and every task that depends on it,
+    /// directly or transitively, so urgent work pulls its prerequisites
+    /// forward. Unknown tasks have priority 0.
+    pub fn effective_priority(&self, task_id: &str) -> i32 {
+        self.effective_priority_with(task_id, &self.dependents_index())
+    }
+
+    /// `effective_priority` against a prebuilt `dependents_index`, so
+    /// callers ranking many tasks build the index once.
+    fn effective_priority_with(&self, task_id: &str, dependents: &HashMap<&str, Vec<&Task>>) -> i32 {
+        let mut priority = match self.tasks.get(task_id) {
+            Some(task) => task.priority,
+            None => return 0,
+        };
+
+        let mut seen = HashSet::from([task_id]);
+        let mut queue = vec![task_id];
+        while let Some(id) = queue.pop() {
+            for dependent in dependents.get(id).into_iter().flatten() {
+                if seen.insert(dependent.id.as_str()) {
+                    priority = priority.max(dependent.priority);
+                    queue.push(&dependent.id);
+                }
+            }
+        }
+        priority
+    }
+
+    /// Reverse dependency edges: each task id to the tasks that list it as
+    /// a dependency.
+    fn dependents_index(&self) -> HashMap<&str, Vec<&Task>> {
+        let mut dependents: HashMap<&str, Vec<&Task>> = HashMap::new();
+        for task in self.tasks.values() {
+            for dep in &task.dependencies {
+                dependents.entry(dep.as_str()).or_default().push(task);
+            }
+        }
+        dependents
+    }
+
+    /// The ready task to dispatch next to a worker with the given zone and
+    /// persona (`None` matches any). Highest `effective_priority` wins, then
+    /// the oldest task, then the lowest id.
+    pub fn next_ready_task(&self, zone: Option<&str>, persona: Option<&str>) -> Option<&Task> {
+        let dependents = self.dependents_index();
+        self.iter_ready_tasks()
+            .filter(|t| zone.is_none_or(|z| t.zone == z))
+            .filter(|t| persona.is_none_or(|p| t.persona == p))
+            .map(|t| (self.effective_priority_with(&t.id, &dependents), t))
+            .min_by(|a, b| dispatch_order(*a, *b))
+            .map(|(_, t)| t)
+    }
+
+    /// Like `get_ready_tasks`, without collecting into a `Vec`.
+    pub fn iter_ready_tasks(&self) -> impl Iterator<Item = &Task> + '_ {
         self.tasks.values()
             .filter(|task| {
                 // Task must be in pending status and all dependencies done
@@ -113,34 +646,205 @@ impl WorkflowEngine {
                 }
 
                 // Check all dependencies are done
-                task.dependencies.iter().all(|dep_id| {
-                    self.tasks.get(dep_id)
-                        .map(|dep| dep.is_done())
-                        .unwrap_or(false)
-                })
+                self.dependencies_done(task)
+            })
+    }
+
+    fn dependencies_done(&self, task: &Task) -> bool {
+        task.dependencies.iter().all(|dep_id| {
+            self.tasks.get(dep_id)
+                .map(|dep| dep.is_done())
+                .unwrap_or(false)
+        })
+    }
+
+    /// Dependencies of `task_id` that aren't done, with their statuses, in
+    /// dependency order. Unknown dependency ids are not included.
+    pub fn blocking_dependencies(&self, task_id: &str) -> Vec<(&Task, &TaskStatus)> {
+        let task = match self.tasks.get(task_id) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+        task.dependencies.iter()
+            .filter_map(|dep_id| self.tasks.get(dep_id))
+            .filter(|dep| !dep.is_done())
+            .map(|dep| (dep, &dep.status))
+            .collect()
+    }
+
+    /// Pending tasks that are up next: every unfinished dependency is
+    /// already in progress. Each comes with those dependencies, in
+    /// declaration order. Ordered like `get_ready_tasks`.
+    pub fn upcoming_tasks(&self) -> Vec<(&Task, Vec<&Task>)> {
+        let dependents = self.dependents_index();
+        let mut upcoming: Vec<(&Task, Vec<&Task>)> = self.tasks.values()
+            .filter(|task| task.status == TaskStatus::Pending)
+            .filter(|task| task.dependencies.iter().all(|dep| self.tasks.contains_key(dep)))
+            .filter_map(|task| {
+                let remaining: Vec<&Task> = self.blocking_dependencies(&task.id).into_iter()
+                    .map(|(dep, _)| dep)
+                    .collect();
+                let up_next = !remaining.is_empty()
+                    && remaining.iter().all(|dep| dep.status == TaskStatus::InProgress);
+                up_next.then_some((task, remaining))
             })
+            .collect();
+        upcoming.sort_by_cached_key(|(task, _)| {
+            (std::cmp::Reverse(self.effective_priority_with(&task.id, &dependents)), task.created_at, task.id.clone())
+        });
+        upcoming
+    }
+
+    /// Why `task_id` isn't ready to start, e.g. "Blocked on task-3
+    /// (in_progress), task-7 (blocked)". `None` if it is ready, already
+    /// marked ready, or unknown.
+    pub fn why_not_ready(&self, task_id: &str) -> Option<String> {
+        let task = self.tasks.get(task_id)?;
+        match &task.status {
+            TaskStatus::Pending => {}
+            TaskStatus::Ready => return None,
+            TaskStatus::Blocked(reason) => return Some(format!("Task is blocked: {}", reason)),
+            status => return Some(format!("Task is already {}", status.as_str())),
+        }
+
+        let mut reasons: Vec<String> = self.blocking_dependencies(task_id).iter()
+            .map(|(dep, status)| format!("{} ({})", dep.id, status.as_str()))
+            .collect();
+        reasons.extend(task.dependencies.iter()
+            .filter(|dep_id| !self.tasks.contains_key(*dep_id))
+            .map(|dep_id| format!("{} (missing)", dep_id)));
+
+        if reasons.is_empty() {
+            None
+        } else {
+            Some(format!("Blocked on {}", reasons.join(", ")))
+        }
+    }
+
+    pub fn get_blocked_tasks(&self) -> Vec<&Task> {
+        self.tasks.values()
+            .filter(|task| task.is_blocked())
+            .collect()
+    }
+
+    pub fn blocked_by_category(&self, category: BlockCategory) -> Vec<&Task> {
+        self.tasks.values()
+            .filter(|task| task.block_reason().is_some_and(|r| r.category == category))
             .collect()
     }
 
+    /// Move a blocked task back into the queue: `Ready` if all of its
+    /// dependencies are done, otherwise `Pending`. The change is logged in
+    /// the task's `status_history`, after the `Blocked` entry with its reason.
+    pub fn unblock_task(&mut self, id: &str) -> Result<(), WorkflowError> {
+        let task = self.tasks.get(id)
+            .ok_or_else(|| WorkflowError::TaskNotFound(id.to_string()))?;
+
+        if !task.is_blocked() {
+            return Err(WorkflowError::InvalidStatusTransition);
+        }
+
+        let status = if self.dependencies_done(task) { TaskStatus::Ready } else { TaskStatus::Pending };
+        self.update_task_status(id, status)
+    }
+
+    /// Case-insensitive substring search over task name and id. Results are
+    /// ranked by earliest match position, then by name and id.
+    pub fn search_tasks(&self, query: &str) -> Vec<&Task> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(usize, &Task)> = self.tasks.values()
+            .filter_map(|task| {
+                let in_name = task.name.to_lowercase().find(&query);
+                let in_id = task.id.to_lowercase().find(&query);
+                let position = match (in_name, in_id) {
+                    (Some(a), Some(b)) => a.min(b),
+                    (a, b) => a.or(b)?,
+                };
+                Some((position, task))
+            })
+            .collect();
+
+        matches.sort_by(|(pa, a), (pb, b)| pa.cmp(pb).then_with(|| a.name.cmp(&b.name)).then_with(|| a.id.cmp(&b.id)));
+        matches.into_iter().map(|(_, task)| task).collect()
+    }
+
+    /// Fuzzy search: the query's characters must appear in order in the
+    /// task name or id (case-insensitive). Ranked by how tightly the
+    /// characters cluster, then by where the match starts.
+    #[cfg(feature = "fuzzy-search")]
+    pub fn search_tasks_fuzzy(&self, query: &str) -> Vec<&Task> {
+        let query: Vec<char> = query.to_lowercase().chars().collect();
+        let mut matches: Vec<((usize, usize), &Task)> = self.tasks.values()
+            .filter_map(|task| {
+                let score = [&task.name, &task.id].iter()
+                    .filter_map(|text| fuzzy_score(&text.to_lowercase(), &query))
+                    .min()?;
+                Some((score, task))
+            })
+            .collect();
+
+        matches.sort_by(|(sa, a), (sb, b)| sa.cmp(sb).then_with(|| a.name.cmp(&b.name)).then_with(|| a.id.cmp(&b.id)));
+        matches.into_iter().map(|(_, task)| task).collect()
+    }
+
     pub fn get_tasks_for_stage(&self, stage: Stage) -> Vec<&Task> {
         self.tasks.values()
             .filter(|task| task.stage == stage)
             .collect()
     }
 
+    /// `get_tasks_for_stage` for the current stage.
+    pub fn current_stage_tasks(&self) -> Vec<&Task> {
+        self.get_tasks_for_stage(self.current_stage)
+    }
+
+    /// `get_ready_tasks` limited to the current stage, in the same order.
+    pub fn current_stage_ready_tasks(&self) -> Vec<&Task> {
+        self.get_ready_tasks()
+            .into_iter()
+            .filter(|task| task.stage == self.current_stage)
+            .collect()
+    }
+
+    pub fn tasks_with_tag(&self, tag: &str) -> Vec<&Task> {
+        self.tasks.values()
+            .filter(|task| task.has_tag(tag))
+            .collect()
+    }
+
+    pub fn tasks_with_all_tags(&self, tags: &[&str]) -> Vec<&Task> {
+        self.tasks.values()
+            .filter(|task| tags.iter().all(|tag| task.has_tag(tag)))
+            .collect()
+    }
+
     pub fn all_tasks(&self) -> Vec<&Task> {
-        self.tasks.values().collect()
+        self.iter_tasks().collect()
+    }
+
+    /// Like `all_tasks`, without collecting into a `Vec`.
+    pub fn iter_tasks(&self) -> impl Iterator<Item = &Task> + '_ {
+        self.tasks.values()
     }
 
     // Gate management
     pub fn get_gate(&self, stage: Stage) -> Option<&Gate> {
-        let id = format!("gate-{}", stage.as_str());
-        self.gates.get(&id)
+        self.gates.get(&Gate::id_for(stage))
     }
 
     pub fn get_gate_mut(&mut self, stage: Stage) -> Option<&mut Gate> {
-        let id = format!("gate-{}", stage.as_str());
-        self.gates.get_mut(&id)
+        self.gates.get_mut(&Gate::id_for(stage))
+    }
+
+    /// Each stage's gate, in pipeline order.
+    pub fn gates(&self) -> impl Iterator<Item = (Stage, &Gate)> + '_ {
+        Stage::all().iter().filter_map(|stage| self.get_gate(*stage).map(|gate| (*stage, gate)))
+    }
+
+    /// `check_gate` for every stage, in pipeline order, from one borrow so
+    /// the statuses are consistent with each other.
+    pub fn all_gate_statuses(&self) -> Vec<(Stage, GateStatus)> {
+        Stage::all().iter().map(|stage| (*stage, self.check_gate(*stage))).collect()
     }
 
     pub fn check_gate(&self, stage: Stage) -> GateStatus {
@@ -149,12 +853,165 @@ impl WorkflowEngine {
             .unwrap_or(GateStatus::Closed)
     }
 
-    pub fn approve_gate(&mut self, stage: Stage, by: &str) -> Result<(), WorkflowError> {
+    /// Whether the gate for `stage` can be approved now, with every reason
+    /// it can't: already approved, previous stage's gate not open, unmet
+    /// criteria, and structural task requirements (integrator, reviewer).
+    pub fn can_approve_gate(&self, stage: Stage) -> (bool, Vec<String>) {
+        let gate = match self.get_gate(stage) {
+            Some(g) => g,
+            None => return (false, vec![format!("No gate for stage {}", stage.as_str())]),
+        };
+
+        let mut reasons = Vec::new();
+        if gate.approved_at.is_some() {
+            reasons.push("Gate already approved".to_string());
+        }
+
+        if let Some(previous) = stage.previous() {
+            if self.check_gate(previous) != GateStatus::Open {
+                reasons.push(format!("Previous stage gate not open: {}", previous.as_str()));
+            }
+        }
+
+        // Structural requirements are recomputed from tasks below, so skip
+        // stale copies persisted as criteria.
+        for description in gate.unmet_criteria() {
+            let structural = description == INTEGRATOR_REQUIREMENT || description == REVIEWER_REQUIREMENT;
+            if !structural {
+                reasons.push(format!("Criterion not met: {}", description));
+            }
+        }
+
+        let is_done = |t: &Task| self.is_terminal_for_stage(t, Task::is_done);
+        match stage {
+            Stage::Implement => reasons.extend(Gate::check_integrator_requirement_with(self.tasks.values(), is_done)),
+            Stage::Verify => reasons.extend(Gate::check_reviewer_requirement_with(self.tasks.values(), is_done)),
+            _ => {}
+        }
+
+        (reasons.is_empty(), reasons)
+    }
+
+    /// Replace the gate for `gate.stage`, e.g. with state loaded from disk.
+    pub fn set_gate(&mut self, gate: Gate) {
+        self.gates.insert(gate.id.clone(), gate);
+        self.sync_zone_gates();
+    }
+
+    /// Keep one implement sub-gate per zone with implement tasks, dropping
+    /// those of zones that no longer have any. A zone's gate is satisfied
+    /// once all of its implement tasks are finished.
+    fn sync_zone_gates(&mut self) {
+        let mut zones: Vec<(String, bool)> = Vec::new();
+        for task in self.tasks.values().filter(|t| t.stage == Stage::Implement) {
+            let finished = self.is_terminal_for_stage(task, |t| matches!(t.status, TaskStatus::Done | TaskStatus::Cancelled));
+            match zones.iter_mut().find(|(zone, _)| *zone == task.zone) {
+                Some((_, all_finished)) => *all_finished &= finished,
+                None => zones.push((task.zone.clone(), finished)),
+            }
+        }
+        zones.sort();
+
+        let gate = match self.get_gate_mut(Stage::Implement) {
+            Some(g) => g,
+            None => return,
+        };
+        gate.children.retain(|child| match &child.zone {
+            Some(zone) => zones.iter().any(|(z, _)| z == zone),
+            None => true,
+        });
+        for (zone, finished) in zones {
+            if gate.child_for_zone(&zone).is_none() {
+                gate.add_child(Gate::for_zone(Stage::Implement, zone.clone()));
+            }
+            let child = gate.child_for_zone_mut(&zone).unwrap();
+            for criterion in &mut child.criteria {
+                criterion.satisfied = finished;
+            }
+            child.update_status();
+        }
+        if gate.approved_at.is_none() {
+            gate.update_status();
+        }
+    }
+
+    /// Approve `stage`'s gate, reporting what auto-advance did as a result.
+    pub fn approve_gate(&mut self, stage: Stage, by: &str) -> Result<AutoAdvance, WorkflowError> {
         let gate = self.get_gate_mut(stage)
             .ok_or(WorkflowError::GateNotFound(stage))?;
 
-        gate.approve(by);
-        Ok(())
+        gate.approve(by)?;
+        Ok(self.advance_after_approval(stage))
+    }
+
+    /// Apply a decision made outside the engine on a request from
+    /// `Gate::to_approval_request`. Auto-advances like `approve_gate`.
+    pub fn apply_approval(&mut self, response: &ApprovalResponse) -> Result<AutoAdvance, WorkflowError> {
+        let gate = self.get_gate_mut(response.stage)
+            .ok_or(WorkflowError::GateNotFound(response.stage))?;
+
+        gate.apply_approval(response)?;
+        Ok(self.advance_after_approval(response.stage))
+    }
+
+    /// With auto-advance on, move past `stage` once its gate is approved
+    /// with every criterion met, if it is the current stage. `transition`'s
+    /// checks still apply; a refusal is returned as `Blocked`.
+    fn advance_after_approval(&mut self, stage: Stage) -> AutoAdvance {
+        if !self.auto_advance || stage != self.current_stage {
+            return AutoAdvance::NotAttempted;
+        }
+        let ready = self.get_gate(stage)
+            .is_some_and(|gate| gate.approved_at.is_some() && gate.all_criteria_satisfied());
+        let next = match stage.next() {
+            Some(next) if ready => next,
+            _ => return AutoAdvance::NotAttempted,
+        };
+        match self.transition(next) {
+            Ok(outcome) => AutoAdvance::Advanced(outcome),
+            Err(e) => AutoAdvance::Blocked(e),
+        }
+    }
+
+    /// Run the checks `approve_gate` would, without changing state. The
+    /// approver name isn't known yet, so only the stage is checked.
+    pub fn approve_gate_dry_run(&self, stage: Stage) -> Result<(), WorkflowError> {
+        self.get_gate(stage)
+            .map(|_| ())
+            .ok_or(WorkflowError::GateNotFound(stage))
+    }
+
+    /// Recompute gate statuses and return every gate whose status or
+    /// satisfied-criteria count changed since the previous poll, in stage
+    /// order. Each change is reported once. Approved gates keep their
+    /// status; only unapproved gates are recomputed.
+    pub fn poll_gate_changes(&mut self) -> Vec<GateChange> {
+        let mut changes = Vec::new();
+
+        for stage in Stage::all() {
+            let gate = match self.get_gate_mut(*stage) {
+                Some(g) => g,
+                None => continue,
+            };
+            if gate.approved_at.is_none() {
+                gate.update_status();
+            }
+            let id = gate.id.clone();
+            let to = gate.status.clone();
+            let progress = gate.progress();
+
+            let from = self.seen_gate_statuses
+                .insert(id.clone(), to.clone())
+                .unwrap_or_default();
+            let previous = self.seen_gate_progress
+                .insert(id, progress)
+                .unwrap_or((0, progress.1));
+            if from != to || previous != progress {
+                changes.push(GateChange { stage: *stage, from, to, progress });
+            }
+        }
+
+        changes
     }
 
     // Serialization
@@ -162,14 +1019,58 @@ impl WorkflowEngine {
         serde_json::to_string(self).unwrap_or_default()
     }
 
+    /// Restore serialized state, rejecting schema versions newer than this
+    /// build and migrating older ones.
     pub fn from_json(json: &str) -> Result<Self, WorkflowError> {
-        serde_json::from_str(json)
-            .map_err(|e| WorkflowError::SerializationError(e.to_string()))
+        let mut engine: Self = serde_json::from_str(json)
+            .map_err(|e| WorkflowError::SerializationError(e.to_string()))?;
+
+        if engine.schema_version > WORKFLOW_SCHEMA_VERSION {
+            return Err(WorkflowError::UnsupportedSchemaVersion {
+                found: engine.schema_version,
+                supported: WORKFLOW_SCHEMA_VERSION,
+            });
+        }
+        // 0 -> 1: fields added before versioning deserialize with defaults.
+        // 1 -> 2: tasks and gates moved from id-keyed maps to sorted arrays,
+        // both of which deserialize.
+        engine.schema_version = WORKFLOW_SCHEMA_VERSION;
+
+        // State from before progress tracking: treat the current progress of
+        // already-polled gates as seen, so the next poll doesn't report it
+        if engine.seen_gate_progress.is_empty() {
+            for gate in engine.gates.values() {
+                if engine.seen_gate_statuses.contains_key(&gate.id) {
+                    engine.seen_gate_progress.insert(gate.id.clone(), gate.progress());
+                }
+            }
+        }
+
+        Ok(engine)
     }
 }
 
-impl Default for WorkflowEngine {
-    fn default() -> Self {
+/// `(span, start)` of the tightest in-order match of `query` in `text`,
+/// in chars; lower is better.
+#[cfg(feature = "fuzzy-search")]
+fn fuzzy_score(text: &str, query: &[char]) -> Option<(usize, usize)> {
+    let text: Vec<char> = text.chars().collect();
+    let first = *query.first()?;
+
+    (0..text.len())
+        .filter(|&start| text[start] == first)
+        .filter_map(|start| {
+            let mut pos = start;
+            for &c in &query[1..] {
+                pos += 1 + text[pos + 1..].iter().position(|&t| t == c)?;
+            }
+            Some((pos - start + 1, start))
+        })
+        .min()
+}
+
+impl Default for WorkflowEngine {
+    fn default() -> Self {
         Self::new()
     }
 }
@@ -208,6 +1109,53 @@ mod tests {
         assert_eq!(retrieved.unwrap().name, "Test task");
     }
 
+    #[test]
+    fn test_try_create_task_rejects_duplicate() {
+        let mut engine = WorkflowEngine::new();
+        let id = engine.try_create_task(Task::new("task-1", "Original", Stage::Discovery, "system", "researcher")).unwrap();
+        assert_eq!(id, "task-1");
+        engine.update_task_status("task-1", TaskStatus::InProgress).unwrap();
+
+        let result = engine.try_create_task(Task::new("task-1", "Resubmitted", Stage::Discovery, "system", "researcher"));
+        assert!(matches!(result, Err(WorkflowError::TaskAlreadyExists(ref id)) if id == "task-1"));
+
+        let task = engine.get_task("task-1").unwrap();
+        assert_eq!(task.name, "Original");
+        assert_eq!(task.status, TaskStatus::InProgress);
+    }
+
+    #[test]
+    fn test_create_task_auto() {
+        let mut engine = WorkflowEngine::new();
+        let first = engine.create_task_auto("Research", Stage::Discovery, "system", "researcher");
+        let second = engine.create_task_auto("Build", Stage::Implement, "backend", "developer");
+        assert_eq!(first, "task-discovery-1");
+        assert_eq!(second, "task-implement-2");
+        assert_eq!(engine.get_task(&second).unwrap().name, "Build");
+    }
+
+    #[test]
+    fn test_create_task_auto_skips_taken_ids_and_persists_counter() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-design-1", "Manual", Stage::Design, "system", "architect"));
+
+        let id = engine.create_task_auto("Auto", Stage::Design, "system", "architect");
+        assert_eq!(id, "task-design-2");
+        assert_eq!(engine.get_task("task-design-1").unwrap().name, "Manual");
+
+        let mut restored = WorkflowEngine::from_json(&engine.to_json()).unwrap();
+        let id = restored.create_task_auto("After restore", Stage::Design, "system", "architect");
+        assert_eq!(id, "task-design-3");
+    }
+
+    #[test]
+    fn test_create_task_overwrites() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Original", Stage::Discovery, "system", "researcher"));
+        engine.create_task(Task::new("task-1", "Replacement", Stage::Discovery, "system", "researcher"));
+        assert_eq!(engine.get_task("task-1").unwrap().name, "Replacement");
+    }
+
     #[test]
     fn test_ready_tasks_with_dependencies() {
         let mut engine = WorkflowEngine::new();
@@ -235,6 +1183,20 @@ mod tests {
         assert_eq!(ready[0].id, "task-2");
     }
 
+    #[test]
+    fn test_iter_tasks() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "First", Stage::Implement, "backend", "developer"));
+        engine.create_task(
+            Task::new("task-2", "Second", Stage::Implement, "backend", "developer")
+                .with_dependencies(vec!["task-1".to_string()]),
+        );
+
+        assert_eq!(engine.iter_tasks().count(), 2);
+        let ready: Vec<&str> = engine.iter_ready_tasks().map(|t| t.id.as_str()).collect();
+        assert_eq!(ready, vec!["task-1"]);
+    }
+
     #[test]
     fn test_stage_transition() {
         let mut engine = WorkflowEngine::new();
@@ -247,15 +1209,656 @@ mod tests {
             for i in 0..gate.criteria.len() {
                 gate.satisfy_criterion(i);
             }
-            gate.approve("user");
+            gate.approve("user").unwrap();
         }
 
         // Now can transition
         assert!(engine.can_transition(Stage::Goal));
+        let outcome = engine.transition(Stage::Goal).unwrap();
+        assert_eq!(engine.current_stage(), Stage::Goal);
+        assert_eq!(outcome.from, Stage::Discovery);
+        assert_eq!(outcome.to, Stage::Goal);
+        assert!(outcome.snapshot_recommended);
+    }
+
+    #[test]
+    fn test_strict_transitions() {
+        let mut engine = WorkflowEngine::new();
+        engine.set_strict_transitions(true);
+        engine.create_task(Task::new("task-1", "Explore", Stage::Discovery, "system", "researcher"));
+        engine.create_task(Task::new("task-2", "Survey", Stage::Discovery, "system", "researcher"));
+
+        if let Some(gate) = engine.get_gate_mut(Stage::Discovery) {
+            for i in 0..gate.criteria.len() {
+                gate.satisfy_criterion(i);
+            }
+            gate.approve("user").unwrap();
+        }
+
+        match engine.transition(Stage::Goal) {
+            Err(WorkflowError::StageTasksIncomplete(ids)) => assert_eq!(ids, vec!["task-1", "task-2"]),
+            other => panic!("expected StageTasksIncomplete, got {:?}", other),
+        }
+        assert_eq!(engine.current_stage(), Stage::Discovery);
+
+        engine.update_task_status("task-1", TaskStatus::Done).unwrap();
+        engine.update_task_status("task-2", TaskStatus::Cancelled).unwrap();
         engine.transition(Stage::Goal).unwrap();
         assert_eq!(engine.current_stage(), Stage::Goal);
     }
 
+    #[test]
+    fn test_update_task_status_rejects_illegal_transition() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Test", Stage::Implement, "backend", "developer"));
+        engine.update_task_status("task-1", TaskStatus::Done).unwrap();
+
+        assert!(matches!(
+            engine.update_task_status("task-1", TaskStatus::InProgress),
+            Err(WorkflowError::InvalidStatusTransition)
+        ));
+        // Repeating the current status is a no-op
+        engine.update_task_status("task-1", TaskStatus::Done).unwrap();
+        let task = engine.get_task("task-1").unwrap();
+        assert!(task.is_done());
+        let history: Vec<&TaskStatus> = task.status_history.iter().map(|(_, s)| s).collect();
+        assert_eq!(history, vec![&TaskStatus::Pending, &TaskStatus::Done]);
+    }
+
+    #[test]
+    fn test_blocked_task_can_be_marked_done() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Test", Stage::Implement, "backend", "developer"));
+        engine.update_task_status("task-1", TaskStatus::Blocked("Waiting on API".into())).unwrap();
+        engine.update_task_status("task-1", TaskStatus::Done).unwrap();
+        assert!(engine.get_task("task-1").unwrap().is_done());
+    }
+
+    #[test]
+    fn test_prune_completed_keeps_needed_tasks() {
+        let mut engine = WorkflowEngine::new();
+        for id in ["old-done", "old-cancelled", "old-needed", "new-done", "active"] {
+            engine.create_task(Task::new(id, id, Stage::Implement, "backend", "developer"));
+        }
+        engine.update_task("active", |t| t.dependencies.push("old-needed".to_string())).unwrap();
+        for id in ["old-done", "old-needed", "new-done"] {
+            engine.update_task_status(id, TaskStatus::InProgress).unwrap();
+            engine.update_task_status(id, TaskStatus::Done).unwrap();
+        }
+        engine.update_task_status("old-cancelled", TaskStatus::Cancelled).unwrap();
+        for id in ["old-done", "old-cancelled", "old-needed"] {
+            engine.tasks.get_mut(id).unwrap().updated_at = 100;
+        }
+
+        let pruned: Vec<String> = engine.prune_completed(200).into_iter().map(|t| t.id).collect();
+        assert_eq!(pruned, vec!["old-cancelled", "old-done"]);
+        assert!(engine.get_task("old-needed").is_some());
+        assert!(engine.get_task("new-done").is_some());
+        assert!(engine.get_task("active").is_some());
+        assert!(engine.prune_completed(200).is_empty());
+    }
+
+    #[test]
+    fn test_rename_task_rewrites_dependencies() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("tsak-1", "Schema", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "API", Stage::Implement, "backend", "developer")
+            .with_dependencies(vec!["tsak-1".to_string()]));
+        engine.create_task(Task::new("task-3", "UI", Stage::Implement, "frontend", "developer")
+            .with_dependencies(vec!["task-2".to_string(), "tsak-1".to_string()]));
+
+        assert!(matches!(engine.rename_task("tsak-1", "task-2"), Err(WorkflowError::TaskAlreadyExists(_))));
+        assert!(matches!(engine.rename_task("missing", "task-9"), Err(WorkflowError::TaskNotFound(_))));
+        assert!(engine.rename_task("task-2", "task-2").is_ok());
+
+        engine.rename_task("tsak-1", "task-1").unwrap();
+        assert!(engine.get_task("tsak-1").is_none());
+        assert_eq!(engine.get_task("task-1").unwrap().id, "task-1");
+        assert_eq!(engine.get_task("task-2").unwrap().dependencies, vec!["task-1".to_string()]);
+        assert_eq!(engine.get_task("task-3").unwrap().dependencies, vec!["task-2".to_string(), "task-1".to_string()]);
+    }
+
+    #[test]
+    fn test_update_task_by_closure() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Schema", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "API", Stage::Implement, "backend", "developer"));
+
+        engine.update_task("task-2", |t| {
+            t.priority = 4;
+            t.dependencies.push("task-1".to_string());
+            t.id = "renamed".to_string();
+        }).unwrap();
+        let task = engine.get_task("task-2").unwrap();
+        assert_eq!(task.priority, 4);
+        assert_eq!(task.dependencies, vec!["task-1".to_string()]);
+        assert_eq!(task.id, "task-2");
+        assert!(engine.get_ready_tasks().iter().all(|t| t.id != "task-2"));
+
+        engine.update_task("task-1", |t| t.status = TaskStatus::Done).unwrap();
+        assert_eq!(engine.get_task("task-1").unwrap().status_history.len(), 2);
+
+        let before = engine.get_task("task-1").unwrap().clone();
+        let result = engine.update_task("task-1", |t| {
+            t.status = TaskStatus::InProgress;
+            t.name = "Schema v2".to_string();
+        });
+        assert!(matches!(result, Err(WorkflowError::InvalidStatusTransition)));
+        let task = engine.get_task("task-1").unwrap();
+        assert!(task.is_done());
+        assert_eq!(task.name, "Schema v2");
+        assert_eq!(task.status_history, before.status_history);
+
+        assert!(matches!(engine.update_task("missing", |_| {}), Err(WorkflowError::TaskNotFound(_))));
+    }
+
+    #[test]
+    fn test_conflicting_tasks_and_exclusive_zones() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "API", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "DB", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-3", "UI", Stage::Implement, "frontend", "developer"));
+
+        engine.assign_task("task-1").unwrap();
+        let conflicts: Vec<&str> = engine.conflicting_tasks("task-2").iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(conflicts, vec!["task-1"]);
+        assert!(engine.conflicting_tasks("task-1").is_empty());
+        assert!(engine.conflicting_tasks("task-3").is_empty());
+
+        engine.set_exclusive_zones(true);
+        assert!(matches!(
+            engine.assign_task("task-2"),
+            Err(WorkflowError::ZoneBusy(zone)) if zone == "backend"
+        ));
+        engine.assign_task("task-3").unwrap();
+
+        engine.update_task_status("task-1", TaskStatus::Done).unwrap();
+        engine.assign_task("task-2").unwrap();
+    }
+
+    #[test]
+    fn test_transition_without_snapshot_hint() {
+        let mut engine = WorkflowEngine::new();
+        engine.set_snapshot_on_transition(false);
+        if let Some(gate) = engine.get_gate_mut(Stage::Discovery) {
+            for i in 0..gate.criteria.len() {
+                gate.satisfy_criterion(i);
+            }
+            gate.approve("user").unwrap();
+        }
+
+        assert!(!engine.transition(Stage::Goal).unwrap().snapshot_recommended);
+    }
+
+    #[test]
+    fn test_to_json_is_sorted_and_stable() {
+        let mut engine = WorkflowEngine::new();
+        for id in ["task-c", "task-a", "task-b"] {
+            engine.create_task(Task::new(id, id, Stage::Design, "backend", "developer"));
+        }
+        engine.poll_gate_changes();
+
+        let json = engine.to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let ids: Vec<&str> = value["tasks"].as_array().unwrap().iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["task-a", "task-b", "task-c"]);
+        assert!(value["gates"].is_array());
+
+        let restored = WorkflowEngine::from_json(&json).unwrap();
+        assert_eq!(restored.to_json(), json);
+        assert!(restored.get_task("task-b").is_some());
+    }
+
+    #[test]
+    fn test_from_json_accepts_keyed_maps() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Legacy", Stage::Design, "backend", "developer"));
+        let mut value: serde_json::Value = serde_json::from_str(&engine.to_json()).unwrap();
+        for field in ["tasks", "gates"] {
+            let keyed: serde_json::Map<String, serde_json::Value> = value[field].as_array().unwrap().iter()
+                .map(|v| (v["id"].as_str().unwrap().to_string(), v.clone()))
+                .collect();
+            value[field] = serde_json::Value::Object(keyed);
+        }
+        value["schema_version"] = serde_json::json!(1);
+
+        let restored = WorkflowEngine::from_json(&value.to_string()).unwrap();
+        assert!(restored.get_task("task-1").is_some());
+        assert!(restored.get_gate(Stage::Release).is_some());
+    }
+
+    #[test]
+    fn test_from_json_schema_version() {
+        let mut value: serde_json::Value = serde_json::from_str(&WorkflowEngine::new().to_json()).unwrap();
+        assert_eq!(value["schema_version"], WORKFLOW_SCHEMA_VERSION);
+
+        value.as_object_mut().unwrap().remove("schema_version");
+        assert!(WorkflowEngine::from_json(&value.to_string()).is_ok());
+
+        value["schema_version"] = serde_json::json!(WORKFLOW_SCHEMA_VERSION + 1);
+        assert!(matches!(
+            WorkflowEngine::from_json(&value.to_string()),
+            Err(WorkflowError::UnsupportedSchemaVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_search_tasks() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Build login API", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "API docs", Stage::Document, "docs", "writer"));
+        engine.create_task(Task::new("task-3", "Design UI", Stage::Design, "frontend", "designer"));
+
+        let ids = |tasks: Vec<&Task>| tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(engine.search_tasks("api")), vec!["task-2", "task-1"]);
+        assert_eq!(ids(engine.search_tasks("TASK-3")), vec!["task-3"]);
+        assert!(engine.search_tasks("deploy").is_empty());
+    }
+
+    #[cfg(feature = "fuzzy-search")]
+    #[test]
+    fn test_search_tasks_fuzzy() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Build login API", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "Login page", Stage::Implement, "frontend", "developer"));
+
+        let ids: Vec<&str> = engine.search_tasks_fuzzy("lgn").iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["task-2", "task-1"]);
+        assert!(engine.search_tasks_fuzzy("zzz").is_empty());
+    }
+
+    #[test]
+    fn test_can_approve_gate() {
+        let mut engine = WorkflowEngine::new();
+
+        let (ok, reasons) = engine.can_approve_gate(Stage::Goal);
+        assert!(!ok);
+        assert_eq!(reasons, vec![
+            "Previous stage gate not open: discovery",
+            "Criterion not met: Goal statement defined",
+            "Criterion not met: Success metrics established",
+        ]);
+
+        for stage in [Stage::Discovery, Stage::Goal] {
+            let gate = engine.get_gate_mut(stage).unwrap();
+            for i in 0..gate.criteria.len() {
+                gate.satisfy_criterion(i);
+            }
+        }
+        assert_eq!(engine.can_approve_gate(Stage::Discovery), (true, vec![]));
+        engine.approve_gate(Stage::Discovery, "user").unwrap();
+        assert_eq!(engine.can_approve_gate(Stage::Goal), (true, vec![]));
+        assert_eq!(engine.can_approve_gate(Stage::Discovery), (false, vec!["Gate already approved".to_string()]));
+    }
+
+    #[test]
+    fn test_can_approve_gate_structural_requirements() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Review", Stage::Verify, "backend", "developer"));
+
+        let (ok, reasons) = engine.can_approve_gate(Stage::Verify);
+        assert!(!ok);
+        assert!(reasons.contains(&REVIEWER_REQUIREMENT.to_string()));
+    }
+
+    #[test]
+    fn test_terminal_statuses_per_stage() {
+        let mut engine = WorkflowEngine::new();
+        engine.set_strict_transitions(true);
+        engine.set_terminal_statuses(Stage::Discovery, vec![TaskStatus::Done]);
+        engine.create_task(Task::new("task-1", "Explore", Stage::Discovery, "system", "researcher"));
+        engine.update_task_status("task-1", TaskStatus::Cancelled).unwrap();
+
+        let gate = engine.get_gate_mut(Stage::Discovery).unwrap();
+        for i in 0..gate.criteria.len() {
+            gate.satisfy_criterion(i);
+        }
+        engine.approve_gate(Stage::Discovery, "user").unwrap();
+
+        // Cancelled no longer counts for discovery
+        assert!(matches!(engine.transition(Stage::Goal), Err(WorkflowError::StageTasksIncomplete(_))));
+
+        engine.set_terminal_statuses(Stage::Discovery, vec![TaskStatus::Done, TaskStatus::Cancelled]);
+        engine.transition(Stage::Goal).unwrap();
+    }
+
+    #[test]
+    fn test_terminal_statuses_in_structural_checks() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Review", Stage::Verify, "backend", "reviewer"));
+        engine.update_task_status("task-1", TaskStatus::InProgress).unwrap();
+
+        let (_, reasons) = engine.can_approve_gate(Stage::Verify);
+        assert!(reasons.contains(&REVIEWER_REQUIREMENT.to_string()));
+
+        engine.set_terminal_statuses(Stage::Verify, vec![TaskStatus::InProgress, TaskStatus::Done]);
+        let (_, reasons) = engine.can_approve_gate(Stage::Verify);
+        assert!(!reasons.contains(&REVIEWER_REQUIREMENT.to_string()));
+    }
+
+    #[test]
+    fn test_implement_zone_sub_gates() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "API", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "UI", Stage::Implement, "frontend", "developer"));
+        engine.create_task(Task::new("task-3", "Merge", Stage::Implement, "backend", "integrator"));
+
+        let gate = engine.get_gate(Stage::Implement).unwrap();
+        assert_eq!(gate.children.len(), 2);
+        assert_eq!(gate.children[0].id, "gate-implement-backend");
+
+        let gate = engine.get_gate_mut(Stage::Implement).unwrap();
+        for i in 0..gate.criteria.len() {
+            gate.satisfy_criterion(i);
+        }
+        assert!(!engine.get_gate(Stage::Implement).unwrap().all_criteria_satisfied());
+
+        for id in ["task-1", "task-2", "task-3"] {
+            engine.update_task_status(id, TaskStatus::Done).unwrap();
+        }
+        let gate = engine.get_gate(Stage::Implement).unwrap();
+        assert!(gate.all_criteria_satisfied());
+        assert_eq!(gate.status, GateStatus::AwaitingApproval);
+    }
+
+    #[test]
+    fn test_zone_sub_gates_follow_task_changes() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "API", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "UI", Stage::Implement, "frontend", "developer"));
+        let zones = |engine: &WorkflowEngine| -> Vec<String> {
+            engine.get_gate(Stage::Implement).unwrap().children.iter()
+                .filter_map(|c| c.zone.clone())
+                .collect()
+        };
+
+        // Moving the only frontend task away drops the frontend sub-gate
+        engine.update_task("task-2", |t| t.zone = "backend".to_string()).unwrap();
+        assert_eq!(zones(&engine), vec!["backend"]);
+
+        // Terminal statuses apply to existing sub-gates immediately
+        engine.update_task_status("task-1", TaskStatus::Cancelled).unwrap();
+        engine.update_task_status("task-2", TaskStatus::Cancelled).unwrap();
+        assert!(engine.get_gate(Stage::Implement).unwrap().children[0].all_criteria_satisfied());
+        engine.set_terminal_statuses(Stage::Implement, vec![TaskStatus::Done]);
+        assert!(!engine.get_gate(Stage::Implement).unwrap().children[0].all_criteria_satisfied());
+
+        // Pruning the zone's last tasks removes its sub-gate
+        engine.prune_completed(u64::MAX);
+        assert!(zones(&engine).is_empty());
+    }
+
+    #[test]
+    fn test_transition_dry_run() {
+        let mut engine = WorkflowEngine::new();
+        assert!(matches!(engine.transition_dry_run(Stage::Goal), Err(WorkflowError::GateNotOpen(Stage::Discovery))));
+        assert!(engine.approve_gate_dry_run(Stage::Discovery).is_ok());
+        assert_eq!(engine.check_gate(Stage::Discovery), GateStatus::Closed);
+
+        engine.approve_gate(Stage::Discovery, "user").unwrap();
+        assert!(engine.transition_dry_run(Stage::Goal).is_ok());
+        assert_eq!(engine.current_stage(), Stage::Discovery);
+
+        engine.set_strict_transitions(true);
+        engine.create_task(Task::new("task-1", "Explore", Stage::Discovery, "system", "researcher"));
+        assert!(matches!(engine.transition_dry_run(Stage::Goal), Err(WorkflowError::StageTasksIncomplete(_))));
+    }
+
+    #[test]
+    fn test_reopen_stage_resets_later_gates() {
+        let mut engine = WorkflowEngine::new();
+        engine.approve_gate(Stage::Discovery, "user").unwrap();
+        engine.transition(Stage::Goal).unwrap();
+        engine.get_gate_mut(Stage::Goal).unwrap().satisfy_by(0, "alice");
+        let request = engine.get_gate(Stage::Goal).unwrap().to_approval_request();
+        engine.approve_gate(Stage::Goal, "user").unwrap();
+        engine.transition(Stage::Requirements).unwrap();
+
+        assert!(matches!(
+            engine.reopen_stage(Stage::Planning),
+            Err(WorkflowError::InvalidTransition { from: Stage::Requirements, to: Stage::Planning })
+        ));
+
+        let reopen = engine.reopen_stage(Stage::Goal).unwrap();
+        assert_eq!(reopen.from, Stage::Requirements);
+        assert_eq!(reopen.to, Stage::Goal);
+        assert_eq!(reopen.reset_gates[0], "gate-goal");
+        assert_eq!(engine.current_stage(), Stage::Goal);
+
+        let goal = engine.get_gate(Stage::Goal).unwrap();
+        assert_eq!(goal.status, GateStatus::Closed);
+        assert!(goal.approved_by.is_none());
+        assert!(goal.criteria.iter().all(|c| !c.satisfied && c.satisfied_by.is_none()));
+        assert_eq!(engine.check_gate(Stage::Discovery), GateStatus::Open);
+        assert!(matches!(engine.transition(Stage::Requirements), Err(WorkflowError::GateNotOpen(Stage::Goal))));
+        assert_eq!(engine.reopen_history().len(), 1);
+
+        // An approval requested before the reopen no longer applies
+        let stale = ApprovalResponse {
+            gate_id: request.gate_id,
+            stage: request.stage,
+            revision: request.revision,
+            approved: true,
+            approved_by: "bob".into(),
+        };
+        assert!(matches!(engine.apply_approval(&stale), Err(WorkflowError::StaleApproval(_))));
+        assert_eq!(engine.check_gate(Stage::Goal), GateStatus::Closed);
+    }
+
+    #[test]
+    fn test_current_stage_tasks() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Explore", Stage::Discovery, "system", "researcher"));
+        engine.create_task(Task::new("task-2", "Interview", Stage::Discovery, "system", "researcher"));
+        engine.create_task(Task::new("task-3", "Build", Stage::Implement, "backend", "developer"));
+        engine.update_task_status("task-2", TaskStatus::InProgress).unwrap();
+
+        assert_eq!(engine.current_stage_tasks().len(), 2);
+        let ready: Vec<&str> = engine.current_stage_ready_tasks().iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ready, vec!["task-1"]);
+    }
+
+    #[test]
+    fn test_all_gate_statuses() {
+        let mut engine = WorkflowEngine::new();
+        engine.approve_gate(Stage::Discovery, "user").unwrap();
+
+        let statuses = engine.all_gate_statuses();
+        assert_eq!(statuses.len(), Stage::all().len());
+        assert_eq!(statuses[0], (Stage::Discovery, GateStatus::Open));
+        assert!(statuses[1..].iter().all(|(_, status)| *status == GateStatus::Closed));
+    }
+
+    #[test]
+    fn test_gates_in_pipeline_order() {
+        let engine = WorkflowEngine::new();
+        let stages: Vec<Stage> = engine.gates().map(|(stage, _)| stage).collect();
+        assert_eq!(stages, Stage::all().to_vec());
+        assert!(engine.gates().all(|(stage, gate)| gate.stage == stage));
+    }
+
+    #[test]
+    fn test_upcoming_tasks() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Schema", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "Auth", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-3", "API", Stage::Implement, "backend", "developer")
+            .with_dependencies(vec!["task-1".to_string()]));
+        engine.create_task(Task::new("task-4", "UI", Stage::Implement, "frontend", "developer")
+            .with_dependencies(vec!["task-1".to_string(), "task-2".to_string()]));
+        engine.create_task(Task::new("task-5", "Docs", Stage::Implement, "docs", "developer")
+            .with_dependencies(vec!["task-0".to_string()]));
+        assert!(engine.upcoming_tasks().is_empty());
+
+        engine.update_task_status("task-1", TaskStatus::InProgress).unwrap();
+        let upcoming = engine.upcoming_tasks();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].0.id, "task-3");
+        assert_eq!(upcoming[0].1.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["task-1"]);
+
+        engine.update_task_status("task-2", TaskStatus::Done).unwrap();
+        let ids: Vec<&str> = engine.upcoming_tasks().iter().map(|(t, _)| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["task-3", "task-4"]);
+
+        // Urgent work downstream of task-4 moves it up
+        engine.create_task(Task::new("task-6", "Launch", Stage::Implement, "frontend", "developer")
+            .with_priority(7)
+            .with_dependencies(vec!["task-4".to_string()]));
+        let ids: Vec<&str> = engine.upcoming_tasks().iter().map(|(t, _)| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["task-4", "task-3"]);
+    }
+
+    #[test]
+    fn test_why_not_ready() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-3", "API", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-5", "Schema", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-7", "Auth", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-9", "UI", Stage::Implement, "frontend", "developer")
+            .with_dependencies(vec!["task-3".into(), "task-5".into(), "task-7".into(), "task-0".into()]));
+        engine.update_task_status("task-3", TaskStatus::InProgress).unwrap();
+        engine.update_task_status("task-5", TaskStatus::Done).unwrap();
+        engine.update_task_status("task-7", TaskStatus::Blocked("Needs keys".into())).unwrap();
+
+        let blocking: Vec<&str> = engine.blocking_dependencies("task-9").iter().map(|(t, _)| t.id.as_str()).collect();
+        assert_eq!(blocking, vec!["task-3", "task-7"]);
+        assert_eq!(
+            engine.why_not_ready("task-9").as_deref(),
+            Some("Blocked on task-3 (in_progress), task-7 (blocked), task-0 (missing)")
+        );
+
+        assert_eq!(engine.why_not_ready("task-5").as_deref(), Some("Task is already done"));
+        assert!(engine.why_not_ready("task-7").unwrap().starts_with("Task is blocked"));
+        assert!(engine.why_not_ready("task-unknown").is_none());
+
+        engine.create_task(Task::new("task-10", "Docs", Stage::Document, "docs", "writer"));
+        assert!(engine.why_not_ready("task-10").is_none());
+    }
+
+    #[test]
+    fn test_next_ready_task() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Low", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "High", Stage::Implement, "backend", "developer").with_priority(5));
+        engine.create_task(Task::new("task-3", "Frontend", Stage::Implement, "frontend", "developer").with_priority(9));
+        engine.create_task(Task::new("task-4", "Blocked on 1", Stage::Implement, "backend", "developer")
+            .with_priority(10)
+            .with_dependencies(vec!["task-1".to_string()]));
+
+        // task-1 inherits task-4's priority 10
+        assert_eq!(engine.next_ready_task(None, None).unwrap().id, "task-1");
+        assert_eq!(engine.next_ready_task(Some("frontend"), None).unwrap().id, "task-3");
+        assert_eq!(engine.next_ready_task(Some("backend"), Some("developer")).unwrap().id, "task-1");
+        assert!(engine.next_ready_task(Some("backend"), Some("reviewer")).is_none());
+
+        engine.update_task_status("task-1", TaskStatus::InProgress).unwrap();
+        assert_eq!(engine.next_ready_task(Some("backend"), None).unwrap().id, "task-2");
+    }
+
+    #[test]
+    fn test_effective_priority_inherits_from_dependents() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Schema", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "API", Stage::Implement, "backend", "developer")
+            .with_dependencies(vec!["task-1".to_string()]));
+        engine.create_task(Task::new("task-3", "Urgent UI", Stage::Implement, "frontend", "developer")
+            .with_priority(8)
+            .with_dependencies(vec!["task-2".to_string()]));
+        engine.create_task(Task::new("task-4", "Docs", Stage::Implement, "docs", "developer").with_priority(3));
+
+        assert_eq!(engine.effective_priority("task-1"), 8);
+        assert_eq!(engine.effective_priority("task-2"), 8);
+        assert_eq!(engine.effective_priority("task-4"), 3);
+        assert_eq!(engine.effective_priority("missing"), 0);
+
+        let ready: Vec<&str> = engine.get_ready_tasks().iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ready, vec!["task-1", "task-4"]);
+    }
+
+    #[test]
+    fn test_effective_priority_tolerates_cycles() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "A", Stage::Implement, "backend", "developer")
+            .with_priority(2)
+            .with_dependencies(vec!["task-2".to_string()]));
+        engine.create_task(Task::new("task-2", "B", Stage::Implement, "backend", "developer")
+            .with_dependencies(vec!["task-1".to_string()]));
+        assert_eq!(engine.effective_priority("task-2"), 2);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(WorkflowError::TaskNotFound("x".into()).code(), "task_not_found");
+        assert_eq!(WorkflowError::GateNotOpen(Stage::Goal).code(), "gate_not_open");
+        assert_eq!(WorkflowError::StageTasksIncomplete(vec![]).code(), "stage_tasks_incomplete");
+        assert_eq!(WorkflowError::AlreadyAtStage(Stage::Goal).code(), "already_at_stage");
+        assert_eq!(WorkflowError::StaleApproval("gate-goal".into()).code(), "stale_approval");
+    }
+
+    #[test]
+    fn test_transition_to_current_stage() {
+        let mut engine = WorkflowEngine::new();
+        let err = engine.transition(Stage::Discovery).unwrap_err();
+        assert!(matches!(err, WorkflowError::AlreadyAtStage(Stage::Discovery)));
+        assert_eq!(err.to_string(), "Already at stage: Discovery");
+        assert_eq!(engine.current_stage(), Stage::Discovery);
+    }
+
+    #[test]
+    fn test_auto_advance_on_approval() {
+        let mut engine = WorkflowEngine::new();
+        let satisfy_all = |engine: &mut WorkflowEngine, stage| {
+            let gate = engine.get_gate_mut(stage).unwrap();
+            for i in 0..gate.criteria.len() {
+                gate.satisfy_criterion(i);
+            }
+        };
+
+        // Off by default
+        satisfy_all(&mut engine, Stage::Discovery);
+        assert!(matches!(engine.approve_gate(Stage::Discovery, "user").unwrap(), AutoAdvance::NotAttempted));
+        assert_eq!(engine.current_stage(), Stage::Discovery);
+
+        engine.set_auto_advance(true);
+        engine.transition(Stage::Goal).unwrap();
+
+        // Unmet criteria and non-current stages don't advance
+        assert!(matches!(engine.approve_gate(Stage::Goal, "user").unwrap(), AutoAdvance::NotAttempted));
+        assert_eq!(engine.current_stage(), Stage::Goal);
+        engine.get_gate_mut(Stage::Goal).unwrap().reset();
+        satisfy_all(&mut engine, Stage::Requirements);
+        assert!(matches!(engine.approve_gate(Stage::Requirements, "user").unwrap(), AutoAdvance::NotAttempted));
+
+        // A refused transition is reported, not swallowed
+        engine.set_strict_transitions(true);
+        engine.create_task(Task::new("task-1", "Define goal", Stage::Goal, "system", "analyst"));
+        satisfy_all(&mut engine, Stage::Goal);
+        assert!(matches!(
+            engine.approve_gate(Stage::Goal, "user").unwrap(),
+            AutoAdvance::Blocked(WorkflowError::StageTasksIncomplete(_))
+        ));
+        assert_eq!(engine.current_stage(), Stage::Goal);
+
+        engine.update_task_status("task-1", TaskStatus::Done).unwrap();
+        match engine.approve_gate(Stage::Goal, "user").unwrap() {
+            AutoAdvance::Advanced(outcome) => assert_eq!((outcome.from, outcome.to), (Stage::Goal, Stage::Requirements)),
+            other => panic!("expected an advance, got {:?}", other),
+        }
+        assert_eq!(engine.current_stage(), Stage::Requirements);
+    }
+
+    #[test]
+    fn test_approve_gate_requires_approver() {
+        let mut engine = WorkflowEngine::new();
+        assert!(matches!(
+            engine.approve_gate(Stage::Discovery, " "),
+            Err(WorkflowError::InvalidApprover)
+        ));
+        assert_eq!(engine.check_gate(Stage::Discovery), GateStatus::Closed);
+    }
+
     #[test]
     fn test_serialization() {
         let mut engine = WorkflowEngine::new();
@@ -285,4 +1888,165 @@ mod tests {
         assert_eq!(implement_tasks.len(), 1);
         assert_eq!(implement_tasks[0].id, "task-2");
     }
+
+    #[test]
+    fn test_tasks_with_tags() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "Login page", Stage::Implement, "frontend", "developer")
+            .with_tags(vec!["frontend".to_string(), "urgent".to_string()]));
+        engine.create_task(Task::new("task-2", "Navbar", Stage::Implement, "frontend", "developer")
+            .with_tags(vec!["frontend".to_string()]));
+        engine.create_task(Task::new("task-3", "DB spike", Stage::Design, "backend", "architect"));
+
+        assert_eq!(engine.tasks_with_tag("frontend").len(), 2);
+        assert!(engine.tasks_with_tag("spike").is_empty());
+
+        let both = engine.tasks_with_all_tags(&["frontend", "urgent"]);
+        assert_eq!(both.len(), 1);
+        assert_eq!(both[0].id, "task-1");
+    }
+
+    #[test]
+    fn test_get_blocked_tasks() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "First", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "Second", Stage::Implement, "backend", "developer"));
+        engine.update_task_status("task-2", TaskStatus::Blocked("Waiting on API".into())).unwrap();
+
+        let blocked = engine.get_blocked_tasks();
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].id, "task-2");
+    }
+
+    #[test]
+    fn test_blocked_by_category() {
+        use crate::task::BlockReason;
+
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "First", Stage::Implement, "backend", "developer"));
+        engine.create_task(Task::new("task-2", "Second", Stage::Implement, "backend", "developer"));
+        engine.update_task_status("task-1", TaskStatus::Blocked(BlockReason::new(BlockCategory::FlakyInfra, "CI timeout"))).unwrap();
+        engine.update_task_status("task-2", TaskStatus::Blocked(BlockReason::new(BlockCategory::NeedsHuman, "Approve spend"))).unwrap();
+
+        let flaky = engine.blocked_by_category(BlockCategory::FlakyInfra);
+        assert_eq!(flaky.len(), 1);
+        assert_eq!(flaky[0].id, "task-1");
+        assert!(engine.blocked_by_category(BlockCategory::ExternalDependency).is_empty());
+    }
+
+    #[test]
+    fn test_unblock_task() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "First", Stage::Implement, "backend", "developer"));
+        engine.create_task(
+            Task::new("task-2", "Second", Stage::Implement, "backend", "developer")
+                .with_dependencies(vec!["task-1".to_string()]),
+        );
+        engine.update_task_status("task-1", TaskStatus::Blocked("flaky".into())).unwrap();
+        engine.update_task_status("task-2", TaskStatus::Blocked("flaky".into())).unwrap();
+
+        // No dependencies -> Ready
+        engine.unblock_task("task-1").unwrap();
+        assert_eq!(engine.get_task("task-1").unwrap().status, TaskStatus::Ready);
+
+        // Dependency not done -> Pending
+        engine.unblock_task("task-2").unwrap();
+        assert_eq!(engine.get_task("task-2").unwrap().status, TaskStatus::Pending);
+
+        // The block and the unblock are both in the history
+        let history: Vec<&TaskStatus> = engine.get_task("task-1").unwrap().status_history.iter().map(|(_, s)| s).collect();
+        assert_eq!(history[history.len() - 2..], [&TaskStatus::Blocked("flaky".into()), &TaskStatus::Ready]);
+    }
+
+    #[test]
+    fn test_unblock_task_errors() {
+        let mut engine = WorkflowEngine::new();
+        engine.create_task(Task::new("task-1", "First", Stage::Implement, "backend", "developer"));
+
+        assert!(matches!(
+            engine.unblock_task("task-1"),
+            Err(WorkflowError::InvalidStatusTransition)
+        ));
+        assert!(matches!(
+            engine.unblock_task("missing"),
+            Err(WorkflowError::TaskNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_poll_gate_changes() {
+        let mut engine = WorkflowEngine::new();
+        assert!(engine.poll_gate_changes().is_empty());
+
+        if let Some(gate) = engine.get_gate_mut(Stage::Discovery) {
+            for i in 0..gate.criteria.len() {
+                gate.satisfy_criterion(i);
+            }
+        }
+
+        let total = engine.get_gate(Stage::Discovery).unwrap().criteria.len();
+        let changes = engine.poll_gate_changes();
+        assert_eq!(changes, vec![GateChange {
+            stage: Stage::Discovery,
+            from: GateStatus::Closed,
+            to: GateStatus::AwaitingApproval,
+            progress: (total, total),
+        }]);
+
+        // Reported only once
+        assert!(engine.poll_gate_changes().is_empty());
+
+        engine.approve_gate(Stage::Discovery, "user").unwrap();
+        let changes = engine.poll_gate_changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].from, GateStatus::AwaitingApproval);
+        assert_eq!(changes[0].to, GateStatus::Open);
+    }
+
+    #[test]
+    fn test_poll_gate_changes_reports_criteria_progress() {
+        let mut engine = WorkflowEngine::new();
+        engine.poll_gate_changes();
+        let total = engine.get_gate(Stage::Discovery).unwrap().criteria.len();
+        assert!(total > 1);
+
+        engine.get_gate_mut(Stage::Discovery).unwrap().satisfy_criterion(0);
+        let changes = engine.poll_gate_changes();
+        assert_eq!(changes, vec![GateChange {
+            stage: Stage::Discovery,
+            from: GateStatus::Closed,
+            to: GateStatus::Closed,
+            progress: (1, total),
+        }]);
+        assert!(engine.poll_gate_changes().is_empty());
+    }
+
+    #[test]
+    fn test_poll_gate_changes_survives_serialization() {
+        let mut engine = WorkflowEngine::new();
+        if let Some(gate) = engine.get_gate_mut(Stage::Discovery) {
+            for i in 0..gate.criteria.len() {
+                gate.satisfy_criterion(i);
+            }
+        }
+        assert_eq!(engine.poll_gate_changes().len(), 1);
+
+        let mut restored = WorkflowEngine::from_json(&engine.to_json()).unwrap();
+        assert!(restored.poll_gate_changes().is_empty());
+    }
+
+    #[test]
+    fn test_poll_gate_changes_after_loading_state_without_progress() {
+        let mut engine = WorkflowEngine::new();
+        engine.get_gate_mut(Stage::Discovery).unwrap().satisfy_criterion(0);
+        assert_eq!(engine.poll_gate_changes().len(), 1);
+
+        let mut state: serde_json::Value = serde_json::from_str(&engine.to_json()).unwrap();
+        state.as_object_mut().unwrap().remove("seen_gate_progress");
+        let mut restored = WorkflowEngine::from_json(&state.to_string()).unwrap();
+        assert!(restored.poll_gate_changes().is_empty());
+
+        restored.get_gate_mut(Stage::Discovery).unwrap().satisfy_criterion(1);
+        assert_eq!(restored.poll_gate_changes().len(), 1);
+    }
 }
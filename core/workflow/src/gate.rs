@@ -1,7 +1,15 @@
 use serde::{Deserialize, Serialize};
+use crate::engine::WorkflowError;
 use crate::stage::Stage;
 use crate::task::Task;
 
+/// Failure message from `Gate::check_integrator_requirement`.
+pub const INTEGRATOR_REQUIREMENT: &str =
+    "Integration task required: multiple implement tasks but no completed integrator task";
+
+/// Failure message from `Gate::check_reviewer_requirement`.
+pub const REVIEWER_REQUIREMENT: &str = "Verify stage requires at least one reviewer task";
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum GateStatus {
@@ -15,6 +23,12 @@ pub enum GateStatus {
 pub struct GateCriterion {
     pub description: String,
     pub satisfied: bool,
+    /// Who or what satisfied the criterion, if recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub satisfied_by: Option<String>,
+    /// Unix seconds when an attributed satisfaction was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub satisfied_at: Option<u64>,
 }
 
 impl GateCriterion {
@@ -22,12 +36,55 @@ impl GateCriterion {
         Self {
             description: description.into(),
             satisfied: false,
+            satisfied_by: None,
+            satisfied_at: None,
         }
     }
 
     pub fn satisfy(&mut self) {
         self.satisfied = true;
     }
+
+    /// Satisfy and record who did it, and when.
+    pub fn satisfy_by(&mut self, who: impl Into<String>) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.satisfied = true;
+        self.satisfied_by = Some(who.into());
+        self.satisfied_at = Some(now);
+    }
+}
+
+/// Standalone snapshot of a gate for approval outside the engine, from
+/// `Gate::to_approval_request`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub gate_id: String,
+    pub stage: Stage,
+    /// The gate's `revision` when the request was made; echo it back in
+    /// the `ApprovalResponse`.
+    pub revision: u64,
+    pub status: GateStatus,
+    /// Own criteria then each child's, prefixed with its zone, with any
+    /// recorded attribution as evidence.
+    pub criteria: Vec<GateCriterion>,
+    /// Descriptions of the criteria still unsatisfied.
+    pub missing: Vec<String>,
+}
+
+/// An external approver's decision, applied with
+/// `WorkflowEngine::apply_approval`. `gate_id` and `revision` come from the
+/// `ApprovalRequest`, so a decision made before the gate was reset is refused.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalResponse {
+    pub gate_id: String,
+    pub stage: Stage,
+    pub revision: u64,
+    pub approved: bool,
+    pub approved_by: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,19 +95,113 @@ pub struct Gate {
     pub criteria: Vec<GateCriterion>,
     pub approved_at: Option<u64>,
     pub approved_by: Option<String>,
+    /// Free-text note left with the approval, e.g. from gates.json.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_note: Option<String>,
+    /// Bumped by every `reset`, to tell a current approval request from one
+    /// made before the stage was reopened.
+    #[serde(default)]
+    pub revision: u64,
+    /// Zone this gate covers, for sub-gates of a stage gate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    /// Sub-gates that must all be satisfied along with this gate's own criteria.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Gate>,
 }
 
 impl Gate {
     pub fn new(stage: Stage) -> Self {
-        let id = format!("gate-{}", stage.as_str());
         Self {
-            id,
+            id: Self::id_for(stage),
             stage,
             status: GateStatus::Closed,
             criteria: Self::default_criteria_for_stage(stage),
             approved_at: None,
             approved_by: None,
+            approval_note: None,
+            revision: 0,
+            zone: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Id of the gate for `stage`: `gate-{stage}`.
+    pub fn id_for(stage: Stage) -> String {
+        format!("gate-{}", stage.as_str())
+    }
+
+    /// Sub-gate for one zone of a stage, with id `gate-{stage}-{zone}`.
+    pub fn for_zone(stage: Stage, zone: impl Into<String>) -> Self {
+        let zone = zone.into();
+        Self {
+            id: format!("gate-{}-{}", stage.as_str(), zone),
+            criteria: vec![GateCriterion::new(format!("All {} tasks complete", zone))],
+            zone: Some(zone),
+            ..Self::new(stage)
+        }
+    }
+
+    pub fn add_child(&mut self, child: Gate) {
+        self.children.push(child);
+    }
+
+    pub fn child_for_zone(&self, zone: &str) -> Option<&Gate> {
+        self.children.iter().find(|c| c.zone.as_deref() == Some(zone))
+    }
+
+    pub fn child_for_zone_mut(&mut self, zone: &str) -> Option<&mut Gate> {
+        self.children.iter_mut().find(|c| c.zone.as_deref() == Some(zone))
+    }
+
+    /// Descriptions of unsatisfied criteria, own first, then each child's
+    /// prefixed with its zone (or id).
+    pub fn unmet_criteria(&self) -> Vec<String> {
+        let mut unmet: Vec<String> = self.criteria.iter()
+            .filter(|c| !c.satisfied)
+            .map(|c| c.description.clone())
+            .collect();
+        for child in &self.children {
+            let label = child.zone.as_deref().unwrap_or(&child.id);
+            unmet.extend(child.unmet_criteria().into_iter().map(|d| format!("{}: {}", label, d)));
+        }
+        unmet
+    }
+
+    pub fn to_approval_request(&self) -> ApprovalRequest {
+        ApprovalRequest {
+            gate_id: self.id.clone(),
+            stage: self.stage,
+            revision: self.revision,
+            status: self.status.clone(),
+            criteria: self.flattened_criteria(),
+            missing: self.unmet_criteria(),
+        }
+    }
+
+    fn flattened_criteria(&self) -> Vec<GateCriterion> {
+        let mut criteria = self.criteria.clone();
+        for child in &self.children {
+            let label = child.zone.as_deref().unwrap_or(&child.id);
+            criteria.extend(child.flattened_criteria().into_iter().map(|c| GateCriterion {
+                description: format!("{}: {}", label, c.description),
+                ..c
+            }));
+        }
+        criteria
+    }
+
+    /// Apply an external decision: approve as `approved_by` when approved,
+    /// leave the gate untouched when rejected. Fails with `StaleApproval`
+    /// when the response is for another gate or an earlier revision.
+    pub fn apply_approval(&mut self, response: &ApprovalResponse) -> Result<(), WorkflowError> {
+        if response.gate_id != self.id || response.revision != self.revision {
+            return Err(WorkflowError::StaleApproval(response.gate_id.clone()));
+        }
+        if response.approved {
+            self.approve(response.approved_by.as_str())?;
         }
+        Ok(())
     }
 
     fn default_criteria_for_stage(stage: Stage) -> Vec<GateCriterion> {
@@ -99,8 +250,20 @@ impl Gate {
         }
     }
 
+    /// Own criteria and every child gate's criteria are satisfied.
     pub fn all_criteria_satisfied(&self) -> bool {
         self.criteria.iter().all(|c| c.satisfied)
+            && self.children.iter().all(|c| c.all_criteria_satisfied())
+    }
+
+    /// Criteria satisfied and criteria total, counting own criteria and
+    /// every child gate's, for "3 of 5 criteria met" style progress.
+    pub fn progress(&self) -> (usize, usize) {
+        let satisfied = self.criteria.iter().filter(|c| c.satisfied).count();
+        self.children.iter().fold((satisfied, self.criteria.len()), |(met, total), child| {
+            let (child_met, child_total) = child.progress();
+            (met + child_met, total + child_total)
+        })
     }
 
     pub fn update_status(&mut self) {
@@ -115,15 +278,42 @@ impl Gate {
         }
     }
 
-    pub fn approve(&mut self, by: impl Into<String>) {
+    /// Return to `Closed`: clear the approval and every criterion, own and
+    /// children's, so the gate must be satisfied and approved again.
+    /// Bumps `revision`, invalidating outstanding approval requests.
+    pub fn reset(&mut self) {
+        for criterion in &mut self.criteria {
+            criterion.satisfied = false;
+            criterion.satisfied_by = None;
+            criterion.satisfied_at = None;
+        }
+        for child in &mut self.children {
+            child.reset();
+        }
+        self.approved_at = None;
+        self.approved_by = None;
+        self.approval_note = None;
+        self.status = GateStatus::Closed;
+        self.revision += 1;
+    }
+
+    /// Approve the gate. The approver name is trimmed and must not be empty.
+    pub fn approve(&mut self, by: impl Into<String>) -> Result<(), WorkflowError> {
+        let by = by.into();
+        let by = by.trim();
+        if by.is_empty() {
+            return Err(WorkflowError::InvalidApprover);
+        }
+
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
 
         self.approved_at = Some(now);
-        self.approved_by = Some(by.into());
+        self.approved_by = Some(by.to_string());
         self.status = GateStatus::Open;
+        Ok(())
     }
 
     pub fn satisfy_criterion(&mut self, index: usize) -> bool {
@@ -136,24 +326,42 @@ impl Gate {
         }
     }
 
+    /// Like `satisfy_criterion`, recording `who` satisfied it.
+    pub fn satisfy_by(&mut self, index: usize, who: impl Into<String>) -> bool {
+        if let Some(criterion) = self.criteria.get_mut(index) {
+            criterion.satisfy_by(who);
+            self.update_status();
+            true
+        } else {
+            false
+        }
+    }
+
     /// Check implement stage gate: if there are multiple implement tasks,
     /// at least one must be an integrator task with status done.
     /// Returns a list of failure messages (empty = pass).
     pub fn check_integrator_requirement(tasks: &[Task]) -> Vec<String> {
+        Self::check_integrator_requirement_with(tasks, Task::is_done)
+    }
+
+    /// `check_integrator_requirement` with a custom notion of "done", over
+    /// any borrowed tasks.
+    pub fn check_integrator_requirement_with<'a>(
+        tasks: impl IntoIterator<Item = &'a Task>,
+        is_done: impl Fn(&Task) -> bool,
+    ) -> Vec<String> {
         let implement_tasks: Vec<&Task> = tasks
-            .iter()
+            .into_iter()
             .filter(|t| t.stage == Stage::Implement)
             .collect();
 
         if implement_tasks.len() > 1 {
             let has_done_integrator = implement_tasks
                 .iter()
-                .any(|t| t.persona == "integrator" && t.is_done());
+                .any(|t| t.persona == "integrator" && is_done(t));
 
             if !has_done_integrator {
-                return vec![
-                    "Integration task required: multiple implement tasks but no completed integrator task".to_string(),
-                ];
+                return vec![INTEGRATOR_REQUIREMENT.to_string()];
             }
         }
 
@@ -163,19 +371,21 @@ impl Gate {
     /// Check verify stage gate: at least one task must have persona "reviewer".
     /// Returns a list of failure messages (empty = pass).
     pub fn check_reviewer_requirement(tasks: &[Task]) -> Vec<String> {
-        let verify_tasks: Vec<&Task> = tasks
-            .iter()
-            .filter(|t| t.stage == Stage::Verify)
-            .collect();
+        Self::check_reviewer_requirement_with(tasks, Task::is_done)
+    }
 
-        let has_reviewer = verify_tasks
-            .iter()
-            .any(|t| t.persona == "reviewer" && t.is_done());
+    /// `check_reviewer_requirement` with a custom notion of "done", over
+    /// any borrowed tasks.
+    pub fn check_reviewer_requirement_with<'a>(
+        tasks: impl IntoIterator<Item = &'a Task>,
+        is_done: impl Fn(&Task) -> bool,
+    ) -> Vec<String> {
+        let has_reviewer = tasks
+            .into_iter()
+            .any(|t| t.stage == Stage::Verify && t.persona == "reviewer" && is_done(t));
 
         if !has_reviewer {
-            return vec![
-                "Verify stage requires at least one reviewer task".to_string(),
-            ];
+            return vec![REVIEWER_REQUIREMENT.to_string()];
         }
 
         vec![]
@@ -205,6 +415,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_progress_counts_children() {
+        let mut gate = Gate::new(Stage::Implement);
+        let own = gate.criteria.len();
+        assert_eq!(gate.progress(), (0, own));
+
+        gate.satisfy_criterion(0);
+        let mut child = Gate::for_zone(Stage::Implement, "frontend");
+        child.criteria = vec![GateCriterion::new("UI built"), GateCriterion::new("UI tested")];
+        child.satisfy_criterion(1);
+        gate.add_child(child);
+
+        assert_eq!(gate.progress(), (2, own + 2));
+    }
+
+    #[test]
+    fn test_zone_children_gate_aggregate() {
+        let mut gate = Gate::new(Stage::Implement);
+        for i in 0..gate.criteria.len() {
+            gate.satisfy_criterion(i);
+        }
+        gate.add_child(Gate::for_zone(Stage::Implement, "backend"));
+        assert_eq!(gate.child_for_zone("backend").unwrap().id, "gate-implement-backend");
+        assert!(!gate.all_criteria_satisfied());
+        assert_eq!(gate.unmet_criteria(), vec!["backend: All backend tasks complete".to_string()]);
+
+        gate.child_for_zone_mut("backend").unwrap().satisfy_criterion(0);
+        gate.update_status();
+        assert!(gate.all_criteria_satisfied());
+        assert_eq!(gate.status, GateStatus::AwaitingApproval);
+    }
+
+    #[test]
+    fn test_satisfy_by_records_attribution() {
+        let mut gate = Gate::new(Stage::Discovery);
+        assert!(gate.satisfy_by(0, "worker-3"));
+        assert_eq!(gate.criteria[0].satisfied_by.as_deref(), Some("worker-3"));
+        assert!(gate.criteria[0].satisfied_at.is_some());
+
+        gate.satisfy_criterion(1);
+        assert!(gate.criteria[1].satisfied);
+        assert!(gate.criteria[1].satisfied_by.is_none());
+        assert!(!gate.satisfy_by(5, "worker-3"));
+    }
+
+    #[test]
+    fn test_approval_request_round_trip() {
+        let mut gate = Gate::new(Stage::Implement);
+        gate.add_child(Gate::for_zone(Stage::Implement, "backend"));
+        gate.satisfy_by(0, "ci");
+
+        let request = gate.to_approval_request();
+        assert_eq!(request.gate_id, "gate-implement");
+        assert_eq!(request.criteria[0].satisfied_by.as_deref(), Some("ci"));
+        assert_eq!(request.criteria.last().unwrap().description, "backend: All backend tasks complete");
+        assert!(request.missing.contains(&"backend: All backend tasks complete".to_string()));
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"missing\""));
+
+        let rejected: ApprovalResponse = serde_json::from_str(
+            r#"{"gate_id": "gate-implement", "stage": "implement", "revision": 0, "approved": false, "approved_by": "bob"}"#,
+        ).unwrap();
+        gate.apply_approval(&rejected).unwrap();
+        assert!(gate.approved_by.is_none());
+
+        let approved = ApprovalResponse { approved: true, ..rejected };
+        gate.apply_approval(&approved).unwrap();
+        assert_eq!(gate.approved_by.as_deref(), Some("bob"));
+
+        // A reset invalidates responses to earlier requests
+        gate.reset();
+        assert!(matches!(gate.apply_approval(&approved), Err(WorkflowError::StaleApproval(_))));
+        assert!(gate.approved_by.is_none());
+        let other_gate = ApprovalResponse { gate_id: "gate-verify".into(), revision: 1, ..approved };
+        assert!(matches!(gate.apply_approval(&other_gate), Err(WorkflowError::StaleApproval(_))));
+    }
+
     #[test]
     fn test_gate_status_progression() {
         let mut gate = Gate::new(Stage::Discovery);
@@ -217,12 +504,23 @@ mod tests {
         assert_eq!(gate.status, GateStatus::AwaitingApproval);
 
         // Approve
-        gate.approve("user");
+        gate.approve("user").unwrap();
         assert_eq!(gate.status, GateStatus::Open);
         assert!(gate.approved_at.is_some());
         assert_eq!(gate.approved_by, Some("user".to_string()));
     }
 
+    #[test]
+    fn test_gate_approve_rejects_empty_approver() {
+        let mut gate = Gate::new(Stage::Discovery);
+        assert!(matches!(gate.approve(""), Err(WorkflowError::InvalidApprover)));
+        assert!(matches!(gate.approve("   "), Err(WorkflowError::InvalidApprover)));
+        assert!(gate.approved_at.is_none());
+
+        gate.approve("  alice ").unwrap();
+        assert_eq!(gate.approved_by, Some("alice".to_string()));
+    }
+
     #[test]
     fn test_gate_serialization() {
         let gate = Gate::new(Stage::Implement);
@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::gate::{Gate, GateCriterion, GateStatus};
+use crate::stage::Stage;
+
+/// On-disk `.mission/state/gates.json`, as written by the Go CLI.
+///
+/// Gates are keyed by stage name. Converting to and from engine `Gate`s
+/// preserves criterion descriptions, per-criterion satisfaction and its
+/// attribution, approval time, approver, approval note and revision.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GatesFile {
+    #[serde(default)]
+    pub gates: BTreeMap<String, GateState>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GateState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    /// `pending`, `ready` or `approved`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub criteria: Vec<CriterionEntry>,
+    /// RFC 3339 UTC timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_note: Option<String>,
+    /// Times the gate has been reset; approvals for an older revision are
+    /// rejected as stale.
+    #[serde(default)]
+    pub revision: u64,
+}
+
+/// A gate criterion — supports both the legacy plain-string format
+/// and the structured format with description + satisfied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CriterionEntry {
+    Plain(String),
+    Structured {
+        description: String,
+        satisfied: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        satisfied_by: Option<String>,
+        /// RFC 3339 UTC timestamp.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        satisfied_at: Option<String>,
+    },
+}
+
+impl CriterionEntry {
+    pub fn description(&self) -> &str {
+        match self {
+            CriterionEntry::Plain(d) => d,
+            CriterionEntry::Structured { description, .. } => description,
+        }
+    }
+
+    pub fn satisfied(&self) -> bool {
+        matches!(self, CriterionEntry::Structured { satisfied: true, .. })
+    }
+
+    fn to_criterion(&self) -> GateCriterion {
+        let mut criterion = GateCriterion::new(self.description());
+        criterion.satisfied = self.satisfied();
+        if let CriterionEntry::Structured { satisfied_by, satisfied_at, .. } = self {
+            criterion.satisfied_by = satisfied_by.clone();
+            criterion.satisfied_at = satisfied_at.as_deref().and_then(parse_rfc3339);
+        }
+        criterion
+    }
+}
+
+impl GateState {
+    fn is_approved(&self) -> bool {
+        self.approved_at.is_some() || self.status.as_deref() == Some("approved")
+    }
+}
+
+impl GatesFile {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Build the engine gate for a stage from its on-disk state. Stages
+    /// with no entry (or no criteria) get the default criteria.
+    pub fn gate_for(&self, stage: Stage) -> Gate {
+        let mut gate = Gate::new(stage);
+        let state = match self.gates.get(stage.as_str()) {
+            Some(s) => s,
+            None => return gate,
+        };
+        gate.revision = state.revision;
+
+        if !state.criteria.is_empty() {
+            gate.criteria = state.criteria.iter().map(CriterionEntry::to_criterion).collect();
+        }
+
+        if state.is_approved() {
+            // Unparseable timestamps still count as approved
+            gate.approved_at = Some(state.approved_at.as_deref().and_then(parse_rfc3339).unwrap_or(0));
+            gate.approved_by = state.approved_by.clone();
+            gate.approval_note = state.approval_note.clone();
+            gate.status = GateStatus::Open;
+        } else {
+            gate.update_status();
+        }
+
+        gate
+    }
+
+    /// Engine gates for every stage, in stage order.
+    pub fn to_engine_gates(&self) -> Vec<Gate> {
+        Stage::all().iter().map(|stage| self.gate_for(*stage)).collect()
+    }
+
+    pub fn from_engine_gates<'a>(gates: impl IntoIterator<Item = &'a Gate>) -> Self {
+        let gates = gates.into_iter()
+            .map(|gate| {
+                let status = match gate.status {
+                    GateStatus::Open => "approved",
+                    GateStatus::AwaitingApproval => "ready",
+                    GateStatus::Closed => "pending",
+                };
+                let state = GateState {
+                    stage: Some(gate.stage.as_str().to_string()),
+                    status: Some(status.to_string()),
+                    criteria: gate.criteria.iter()
+                        .map(|c| CriterionEntry::Structured {
+                            description: c.description.clone(),
+                            satisfied: c.satisfied,
+                            satisfied_by: c.satisfied_by.clone(),
+                            satisfied_at: c.satisfied_at.map(format_rfc3339),
+                        })
+                        .collect(),
+                    approved_at: gate.approved_at.map(format_rfc3339),
+                    approved_by: gate.approved_by.clone(),
+                    approval_note: gate.approval_note.clone(),
+                    revision: gate.revision,
+                };
+                (gate.stage.as_str().to_string(), state)
+            })
+            .collect();
+
+        Self { gates }
+    }
+}
+
+/// Parse an RFC 3339 timestamp into Unix seconds.
+fn parse_rfc3339(s: &str) -> Option<u64> {
+    let secs = DateTime::parse_from_rfc3339(s).ok()?.timestamp();
+    u64::try_from(secs).ok()
+}
+
+fn format_rfc3339(secs: u64) -> String {
+    i64::try_from(secs).ok()
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{WorkflowEngine, WorkflowError};
+    use crate::gate::ApprovalResponse;
+
+    #[test]
+    fn test_rfc3339_round_trip() {
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_rfc3339("2026-02-10T12:30:45Z"), Some(1770726645));
+        assert_eq!(parse_rfc3339("2026-02-10T14:30:45.123+02:00"), Some(1770726645));
+        assert_eq!(format_rfc3339(1770726645), "2026-02-10T12:30:45Z");
+        assert_eq!(parse_rfc3339("yesterday"), None);
+        assert_eq!(parse_rfc3339("1969-12-31T23:59:59Z"), None);
+    }
+
+    #[test]
+    fn test_reads_go_format() {
+        let json = r#"{
+            "gates": {
+                "design": {
+                    "criteria": [
+                        {"description": "Spec document complete", "satisfied": true},
+                        {"description": "Custom check", "satisfied": false}
+                    ]
+                },
+                "discovery": {
+                    "stage": "discovery",
+                    "status": "approved",
+                    "criteria": ["Problem space explored"],
+                    "approved_at": "2026-02-10T12:30:45Z",
+                    "approval_note": "looks good"
+                }
+            }
+        }"#;
+
+        let file = GatesFile::from_json(json).unwrap();
+
+        let design = file.gate_for(Stage::Design);
+        assert_eq!(design.criteria.len(), 2);
+        assert!(design.criteria[0].satisfied);
+        assert_eq!(design.criteria[1].description, "Custom check");
+        assert_eq!(design.status, GateStatus::Closed);
+
+        let discovery = file.gate_for(Stage::Discovery);
+        assert_eq!(discovery.status, GateStatus::Open);
+        assert_eq!(discovery.approved_at, Some(1770726645));
+        assert_eq!(discovery.approved_by, None);
+        assert_eq!(discovery.approval_note.as_deref(), Some("looks good"));
+
+        // Writing back keeps the note and the missing approver as they were
+        let written = GatesFile::from_engine_gates(&file.to_engine_gates());
+        let state = &written.gates["discovery"];
+        assert_eq!(state.approval_note.as_deref(), Some("looks good"));
+        assert_eq!(state.approved_by, None);
+        assert_eq!(state.approved_at.as_deref(), Some("2026-02-10T12:30:45Z"));
+
+        // Stage with no entry falls back to defaults
+        let goal = file.gate_for(Stage::Goal);
+        assert_eq!(goal.criteria.len(), Gate::new(Stage::Goal).criteria.len());
+    }
+
+    #[test]
+    fn test_engine_gates_round_trip() {
+        let mut gates: Vec<Gate> = Stage::all().iter().map(|s| Gate::new(*s)).collect();
+        gates[0].satisfy_criterion(0);
+        gates[0].satisfy_criterion(1);
+        gates[0].approve("alice").unwrap();
+        gates[0].approved_at = Some(1770726645);
+        gates[0].approval_note = Some("ship it".to_string());
+        gates[4].satisfy_criterion(1);
+        gates[5].satisfy_by(0, "worker-3");
+        gates[5].criteria[0].satisfied_at = Some(1770726645);
+
+        let json = GatesFile::from_engine_gates(&gates).to_json().unwrap();
+        let restored = GatesFile::from_json(&json).unwrap().to_engine_gates();
+
+        assert_eq!(restored.len(), gates.len());
+        for (before, after) in gates.iter().zip(&restored) {
+            assert_eq!(before.stage, after.stage);
+            assert_eq!(before.status, after.status);
+            assert_eq!(before.approved_at, after.approved_at);
+            assert_eq!(before.approved_by, after.approved_by);
+            assert_eq!(before.approval_note, after.approval_note);
+            assert_eq!(before.revision, after.revision);
+            let flags = |g: &Gate| g.criteria.iter()
+                .map(|c| (c.description.clone(), c.satisfied, c.satisfied_by.clone(), c.satisfied_at))
+                .collect::<Vec<_>>();
+            assert_eq!(flags(before), flags(after));
+        }
+    }
+
+    #[test]
+    fn test_reopened_gate_revision_survives_save() {
+        let mut engine = WorkflowEngine::new();
+        let request = engine.get_gate(Stage::Discovery).unwrap().to_approval_request();
+        engine.approve_gate(Stage::Discovery, "user").unwrap();
+        engine.transition(Stage::Goal).unwrap();
+        engine.reopen_stage(Stage::Discovery).unwrap();
+
+        let json = GatesFile::from_engine_gates(engine.gates().map(|(_, gate)| gate)).to_json().unwrap();
+        let mut loaded = WorkflowEngine::new();
+        for gate in GatesFile::from_json(&json).unwrap().to_engine_gates() {
+            loaded.set_gate(gate);
+        }
+        assert_eq!(loaded.get_gate(Stage::Discovery).unwrap().revision, 1);
+
+        let stale = ApprovalResponse {
+            gate_id: request.gate_id,
+            stage: request.stage,
+            revision: request.revision,
+            approved: true,
+            approved_by: "bob".into(),
+        };
+        assert!(matches!(loaded.apply_approval(&stale), Err(WorkflowError::StaleApproval(_))));
+        assert_eq!(loaded.check_gate(Stage::Discovery), GateStatus::Closed);
+    }
+}
@@ -1,9 +1,15 @@
 mod stage;
 mod task;
+mod lifecycle;
 mod gate;
 mod engine;
+mod gates_file;
+mod shared;
 
-pub use stage::Stage;
-pub use task::{Task, TaskStatus};
-pub use gate::{Gate, GateCriterion, GateStatus};
-pub use engine::{WorkflowEngine, WorkflowError};
+pub use stage::{ParseStageError, Stage};
+pub use task::{BlockCategory, BlockReason, Task, TaskStatus};
+pub use lifecycle::TaskStateMachine;
+pub use gate::{ApprovalRequest, ApprovalResponse, Gate, GateCriterion, GateStatus, INTEGRATOR_REQUIREMENT, REVIEWER_REQUIREMENT};
+pub use gates_file::{CriterionEntry, GateState, GatesFile};
+pub use engine::{AutoAdvance, GateChange, StageReopen, TransitionOutcome, WorkflowEngine, WorkflowError, WORKFLOW_SCHEMA_VERSION};
+pub use shared::SharedWorkflowEngine;
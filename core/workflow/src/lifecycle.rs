@@ -0,0 +1,89 @@
+use std::mem::discriminant;
+
+use crate::task::{BlockReason, TaskStatus};
+
+/// The task lifecycle: which status changes are legal.
+///
+/// Live tasks (pending, ready, in_progress, blocked) can move between each
+/// other, be marked done, or be cancelled. `done` and `cancelled` are
+/// terminal. A blocked task may be re-blocked with a new reason; the reason
+/// never affects legality.
+pub struct TaskStateMachine;
+
+impl TaskStateMachine {
+    /// Statuses reachable from `from`, in lifecycle order. `Blocked`
+    /// appears with an empty reason; callers fill in their own.
+    pub fn allowed_next(from: &TaskStatus) -> Vec<TaskStatus> {
+        let blocked = || TaskStatus::Blocked(BlockReason::other(""));
+        match from {
+            TaskStatus::Pending => vec![
+                TaskStatus::Ready,
+                TaskStatus::InProgress,
+                blocked(),
+                TaskStatus::Done,
+                TaskStatus::Cancelled,
+            ],
+            TaskStatus::Ready => vec![
+                TaskStatus::Pending,
+                TaskStatus::InProgress,
+                blocked(),
+                TaskStatus::Done,
+                TaskStatus::Cancelled,
+            ],
+            TaskStatus::InProgress => vec![
+                TaskStatus::Pending,
+                TaskStatus::Ready,
+                blocked(),
+                TaskStatus::Done,
+                TaskStatus::Cancelled,
+            ],
+            TaskStatus::Blocked(_) => vec![
+                TaskStatus::Pending,
+                TaskStatus::Ready,
+                TaskStatus::InProgress,
+                blocked(),
+                TaskStatus::Done,
+                TaskStatus::Cancelled,
+            ],
+            TaskStatus::Done | TaskStatus::Cancelled => Vec::new(),
+        }
+    }
+
+    pub fn can_transition(from: &TaskStatus, to: &TaskStatus) -> bool {
+        Self::allowed_next(from)
+            .iter()
+            .any(|next| discriminant(next) == discriminant(to))
+    }
+
+    pub fn is_terminal(status: &TaskStatus) -> bool {
+        Self::allowed_next(status).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_states() {
+        assert!(TaskStateMachine::is_terminal(&TaskStatus::Done));
+        assert!(TaskStateMachine::is_terminal(&TaskStatus::Cancelled));
+        assert!(!TaskStateMachine::is_terminal(&TaskStatus::Pending));
+        assert!(!TaskStateMachine::can_transition(&TaskStatus::Done, &TaskStatus::InProgress));
+    }
+
+    #[test]
+    fn test_block_reason_does_not_affect_legality() {
+        let blocked = TaskStatus::Blocked("Waiting on API".into());
+        assert!(TaskStateMachine::can_transition(&TaskStatus::InProgress, &blocked));
+        assert!(TaskStateMachine::can_transition(&blocked, &TaskStatus::Blocked("Still waiting".into())));
+        assert!(TaskStateMachine::can_transition(&blocked, &TaskStatus::Done));
+    }
+
+    #[test]
+    fn test_allowed_next_excludes_self_for_live_states() {
+        for status in [TaskStatus::Pending, TaskStatus::Ready, TaskStatus::InProgress] {
+            assert!(!TaskStateMachine::can_transition(&status, &status));
+        }
+    }
+}
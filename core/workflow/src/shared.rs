@@ -0,0 +1,258 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::engine::{AutoAdvance, GateChange, StageReopen, TransitionOutcome, WorkflowEngine, WorkflowError};
+use crate::gate::{ApprovalResponse, Gate, GateStatus};
+use crate::stage::Stage;
+use crate::task::{Task, TaskStatus};
+
+/// Thread-safe handle to a `WorkflowEngine`.
+///
+/// Queries take a read lock and return owned data, so many readers can run
+/// concurrently; mutations take the write lock. Clones share the same
+/// engine. Single-threaded callers should keep using `WorkflowEngine`.
+///
+/// Read-only: `current_stage`, `can_transition`, `transition_dry_run`, `get_task`,
+/// `get_ready_tasks`, `next_ready_task`, `upcoming_tasks`, `effective_priority`,
+/// `get_tasks_for_stage`, `current_stage_tasks`, `current_stage_ready_tasks`,
+/// `get_blocked_tasks`, `conflicting_tasks`, `all_tasks`, `get_gate`,
+/// `check_gate`, `all_gate_statuses`, `can_approve_gate`, `reopen_history`,
+/// `to_json`, `read`.
+///
+/// Mutating: `transition`, `reopen_stage`, `create_task`, `try_create_task`,
+/// `update_task`, `rename_task`, `prune_completed`, `update_task_status`,
+/// `assign_task`, `unblock_task`, `approve_gate`, `apply_approval`,
+/// `poll_gate_changes`, `write`.
+#[derive(Debug, Clone, Default)]
+pub struct SharedWorkflowEngine {
+    inner: Arc<RwLock<WorkflowEngine>>,
+}
+
+impl SharedWorkflowEngine {
+    pub fn new() -> Self {
+        Self::from_engine(WorkflowEngine::new())
+    }
+
+    pub fn from_engine(engine: WorkflowEngine) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(engine)),
+        }
+    }
+
+    // Recover from poisoning rather than propagating another thread's panic.
+    fn read_guard(&self) -> RwLockReadGuard<'_, WorkflowEngine> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write_guard(&self) -> RwLockWriteGuard<'_, WorkflowEngine> {
+        self.inner.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run a query against the engine under a read lock.
+    pub fn read<R>(&self, f: impl FnOnce(&WorkflowEngine) -> R) -> R {
+        f(&self.read_guard())
+    }
+
+    /// Run a mutation against the engine under the write lock.
+    pub fn write<R>(&self, f: impl FnOnce(&mut WorkflowEngine) -> R) -> R {
+        f(&mut self.write_guard())
+    }
+
+    // Read-only queries
+    pub fn current_stage(&self) -> Stage {
+        self.read_guard().current_stage()
+    }
+
+    pub fn can_transition(&self, to: Stage) -> bool {
+        self.read_guard().can_transition(to)
+    }
+
+    pub fn transition_dry_run(&self, to: Stage) -> Result<(), WorkflowError> {
+        self.read_guard().transition_dry_run(to)
+    }
+
+    pub fn get_task(&self, id: &str) -> Option<Task> {
+        self.read_guard().get_task(id).cloned()
+    }
+
+    pub fn get_ready_tasks(&self) -> Vec<Task> {
+        self.read_guard().get_ready_tasks().into_iter().cloned().collect()
+    }
+
+    pub fn next_ready_task(&self, zone: Option<&str>, persona: Option<&str>) -> Option<Task> {
+        self.read_guard().next_ready_task(zone, persona).cloned()
+    }
+
+    pub fn upcoming_tasks(&self) -> Vec<(Task, Vec<Task>)> {
+        self.read_guard().upcoming_tasks().into_iter()
+            .map(|(task, deps)| (task.clone(), deps.into_iter().cloned().collect()))
+            .collect()
+    }
+
+    pub fn effective_priority(&self, task_id: &str) -> i32 {
+        self.read_guard().effective_priority(task_id)
+    }
+
+    pub fn get_tasks_for_stage(&self, stage: Stage) -> Vec<Task> {
+        self.read_guard().get_tasks_for_stage(stage).into_iter().cloned().collect()
+    }
+
+    pub fn current_stage_tasks(&self) -> Vec<Task> {
+        self.read_guard().current_stage_tasks().into_iter().cloned().collect()
+    }
+
+    pub fn current_stage_ready_tasks(&self) -> Vec<Task> {
+        self.read_guard().current_stage_ready_tasks().into_iter().cloned().collect()
+    }
+
+    pub fn get_blocked_tasks(&self) -> Vec<Task> {
+        self.read_guard().get_blocked_tasks().into_iter().cloned().collect()
+    }
+
+    pub fn conflicting_tasks(&self, task_id: &str) -> Vec<Task> {
+        self.read_guard().conflicting_tasks(task_id).into_iter().cloned().collect()
+    }
+
+    pub fn all_tasks(&self) -> Vec<Task> {
+        self.read_guard().all_tasks().into_iter().cloned().collect()
+    }
+
+    pub fn get_gate(&self, stage: Stage) -> Option<Gate> {
+        self.read_guard().get_gate(stage).cloned()
+    }
+
+    pub fn check_gate(&self, stage: Stage) -> GateStatus {
+        self.read_guard().check_gate(stage)
+    }
+
+    pub fn all_gate_statuses(&self) -> Vec<(Stage, GateStatus)> {
+        self.read_guard().all_gate_statuses()
+    }
+
+    pub fn can_approve_gate(&self, stage: Stage) -> (bool, Vec<String>) {
+        self.read_guard().can_approve_gate(stage)
+    }
+
+    pub fn reopen_history(&self) -> Vec<StageReopen> {
+        self.read_guard().reopen_history().to_vec()
+    }
+
+    pub fn to_json(&self) -> String {
+        self.read_guard().to_json()
+    }
+
+    // Mutations
+    pub fn transition(&self, to: Stage) -> Result<TransitionOutcome, WorkflowError> {
+        self.write_guard().transition(to)
+    }
+
+    pub fn reopen_stage(&self, stage: Stage) -> Result<StageReopen, WorkflowError> {
+        self.write_guard().reopen_stage(stage)
+    }
+
+    pub fn create_task(&self, task: Task) -> String {
+        self.write_guard().create_task(task)
+    }
+
+    pub fn try_create_task(&self, task: Task) -> Result<String, WorkflowError> {
+        self.write_guard().try_create_task(task)
+    }
+
+    pub fn update_task<F: FnOnce(&mut Task)>(&self, id: &str, f: F) -> Result<(), WorkflowError> {
+        self.write_guard().update_task(id, f)
+    }
+
+    pub fn rename_task(&self, old_id: &str, new_id: &str) -> Result<(), WorkflowError> {
+        self.write_guard().rename_task(old_id, new_id)
+    }
+
+    pub fn prune_completed(&self, before: u64) -> Vec<Task> {
+        self.write_guard().prune_completed(before)
+    }
+
+    pub fn update_task_status(&self, id: &str, status: TaskStatus) -> Result<(), WorkflowError> {
+        self.write_guard().update_task_status(id, status)
+    }
+
+    pub fn assign_task(&self, id: &str) -> Result<(), WorkflowError> {
+        self.write_guard().assign_task(id)
+    }
+
+    pub fn unblock_task(&self, id: &str) -> Result<(), WorkflowError> {
+        self.write_guard().unblock_task(id)
+    }
+
+    pub fn approve_gate(&self, stage: Stage, by: &str) -> Result<AutoAdvance, WorkflowError> {
+        self.write_guard().approve_gate(stage, by)
+    }
+
+    pub fn apply_approval(&self, response: &ApprovalResponse) -> Result<AutoAdvance, WorkflowError> {
+        self.write_guard().apply_approval(response)
+    }
+
+    pub fn poll_gate_changes(&self) -> Vec<GateChange> {
+        self.write_guard().poll_gate_changes()
+    }
+}
+
+impl From<WorkflowEngine> for SharedWorkflowEngine {
+    fn from(engine: WorkflowEngine) -> Self {
+        Self::from_engine(engine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_shared_engine_clones_share_state() {
+        let shared = SharedWorkflowEngine::new();
+        let other = shared.clone();
+
+        shared.create_task(Task::new("task-1", "Test", Stage::Discovery, "system", "researcher"));
+        assert!(other.get_task("task-1").is_some());
+        assert_eq!(other.current_stage(), Stage::Discovery);
+    }
+
+    #[test]
+    fn test_shared_engine_concurrent_updates() {
+        let shared = SharedWorkflowEngine::new();
+        for i in 0..8 {
+            shared.create_task(Task::new(format!("task-{}", i), "Work", Stage::Implement, "backend", "developer"));
+        }
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let engine = shared.clone();
+                thread::spawn(move || {
+                    engine.update_task_status(&format!("task-{}", i), TaskStatus::Done).unwrap();
+                    engine.all_tasks().len()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 8);
+        }
+        assert!(shared.all_tasks().iter().all(|t| t.is_done()));
+    }
+
+    #[test]
+    fn test_shared_engine_read_write_closures() {
+        let shared = SharedWorkflowEngine::new();
+        shared.write(|engine| {
+            if let Some(gate) = engine.get_gate_mut(Stage::Discovery) {
+                for i in 0..gate.criteria.len() {
+                    gate.satisfy_criterion(i);
+                }
+            }
+        });
+
+        assert_eq!(shared.check_gate(Stage::Discovery), GateStatus::AwaitingApproval);
+        shared.approve_gate(Stage::Discovery, "user").unwrap();
+        assert!(shared.read(|engine| engine.can_transition(Stage::Goal)));
+        shared.transition(Stage::Goal).unwrap();
+        assert_eq!(shared.current_stage(), Stage::Goal);
+    }
+}
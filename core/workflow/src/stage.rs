@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Unknown stage '{0}'. Valid: discovery, goal, requirements, planning, design, implement, verify, validate, document, release")]
+pub struct ParseStageError(pub String);
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Stage {
     #[default]
@@ -32,6 +38,10 @@ impl Stage {
         }
     }
 
+    pub fn previous(&self) -> Option<Stage> {
+        Stage::all().iter().copied().find(|s| s.next() == Some(*self))
+    }
+
     pub fn all() -> &'static [Stage] {
         &[
             Stage::Discovery,
@@ -63,6 +73,19 @@ impl Stage {
     }
 }
 
+impl std::str::FromStr for Stage {
+    type Err = ParseStageError;
+
+    /// Parse a lowercase stage name, as produced by `as_str`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Stage::all()
+            .iter()
+            .find(|stage| stage.as_str() == s)
+            .copied()
+            .ok_or_else(|| ParseStageError(s.to_string()))
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -124,4 +147,22 @@ mod tests {
     fn test_stage_default() {
         assert_eq!(Stage::default(), Stage::Discovery);
     }
+
+    #[test]
+    fn test_stage_previous() {
+        assert_eq!(Stage::Discovery.previous(), None);
+        assert_eq!(Stage::Goal.previous(), Some(Stage::Discovery));
+        assert_eq!(Stage::Release.previous(), Some(Stage::Document));
+    }
+
+    #[test]
+    fn test_stage_from_str() {
+        for stage in Stage::all() {
+            assert_eq!(stage.as_str().parse::<Stage>(), Ok(*stage));
+        }
+
+        let err = "deploy".parse::<Stage>().unwrap_err();
+        assert_eq!(err, ParseStageError("deploy".to_string()));
+        assert!(err.to_string().contains("implement"));
+    }
 }
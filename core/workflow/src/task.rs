@@ -1,6 +1,113 @@
 use serde::{Deserialize, Serialize};
 use crate::stage::Stage;
 
+/// Broad kind of blocker, used to route blocked work to the right place.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockCategory {
+    /// Waiting on something outside the mission (API access, another team).
+    ExternalDependency,
+    /// Needs a human decision or input.
+    NeedsHuman,
+    /// Flaky CI, tooling or environment failures.
+    FlakyInfra,
+    #[default]
+    Other,
+}
+
+impl BlockCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockCategory::ExternalDependency => "external_dependency",
+            BlockCategory::NeedsHuman => "needs_human",
+            BlockCategory::FlakyInfra => "flaky_infra",
+            BlockCategory::Other => "other",
+        }
+    }
+}
+
+/// Why a task or handoff is blocked.
+///
+/// Deserializes from either `{"category": ..., "detail": ...}` or a legacy
+/// plain string, which maps to `BlockCategory::Other`. Its JSON Schema
+/// describes both accepted forms; serialization always writes the first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "BlockReasonRepr")]
+pub struct BlockReason {
+    pub category: BlockCategory,
+    pub detail: String,
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BlockReasonRepr {
+    Legacy(String),
+    Structured {
+        #[serde(default)]
+        category: BlockCategory,
+        #[serde(default)]
+        detail: String,
+    },
+}
+
+impl From<BlockReasonRepr> for BlockReason {
+    fn from(repr: BlockReasonRepr) -> Self {
+        match repr {
+            BlockReasonRepr::Legacy(detail) => BlockReason::other(detail),
+            BlockReasonRepr::Structured { category, detail } => BlockReason { category, detail },
+        }
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for BlockReason {
+    fn schema_name() -> String {
+        "BlockReason".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        BlockReasonRepr::json_schema(gen)
+    }
+}
+
+impl BlockReason {
+    pub fn new(category: BlockCategory, detail: impl Into<String>) -> Self {
+        Self {
+            category,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn other(detail: impl Into<String>) -> Self {
+        Self::new(BlockCategory::Other, detail)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.detail.is_empty()
+    }
+}
+
+impl From<String> for BlockReason {
+    fn from(detail: String) -> Self {
+        BlockReason::other(detail)
+    }
+}
+
+impl From<&str> for BlockReason {
+    fn from(detail: &str) -> Self {
+        BlockReason::other(detail)
+    }
+}
+
+impl std::fmt::Display for BlockReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.category.as_str(), self.detail)
+    }
+}
+
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
@@ -8,8 +115,9 @@ pub enum TaskStatus {
     Pending,
     Ready,
     InProgress,
-    Blocked(String),
+    Blocked(BlockReason),
     Done,
+    Cancelled,
 }
 
 impl TaskStatus {
@@ -20,11 +128,13 @@ impl TaskStatus {
             TaskStatus::InProgress => "in_progress",
             TaskStatus::Blocked(_) => "blocked",
             TaskStatus::Done => "done",
+            TaskStatus::Cancelled => "cancelled",
         }
     }
 }
 
 
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
@@ -34,8 +144,17 @@ pub struct Task {
     pub status: TaskStatus,
     pub persona: String,
     pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Dispatch priority; higher runs first.
+    #[serde(default)]
+    pub priority: i32,
     pub created_at: u64,
     pub updated_at: u64,
+    /// `(unix_secs, status)` for the initial status and every change
+    /// made through `WorkflowEngine::update_task_status`, oldest first.
+    #[serde(default)]
+    pub status_history: Vec<(u64, TaskStatus)>,
 }
 
 impl Task {
@@ -59,8 +178,11 @@ impl Task {
             status: TaskStatus::Pending,
             persona: persona.into(),
             dependencies: Vec::new(),
+            tags: Vec::new(),
+            priority: 0,
             created_at: now,
             updated_at: now,
+            status_history: vec![(now, TaskStatus::Pending)],
         }
     }
 
@@ -69,13 +191,58 @@ impl Task {
         self
     }
 
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
     pub fn is_blocked(&self) -> bool {
         matches!(self.status, TaskStatus::Blocked(_))
     }
 
+    pub fn block_reason(&self) -> Option<&BlockReason> {
+        match &self.status {
+            TaskStatus::Blocked(reason) => Some(reason),
+            _ => None,
+        }
+    }
+
     pub fn is_done(&self) -> bool {
         matches!(self.status, TaskStatus::Done)
     }
+
+    /// Total seconds spent in `status` according to `status_history`,
+    /// counting the latest entry as running until now. Blocked matches
+    /// regardless of reason.
+    pub fn time_in_status(&self, status: &TaskStatus) -> u64 {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.time_in_status_at(status, now)
+    }
+
+    /// Like `time_in_status`, measuring the latest entry up to `now`.
+    pub fn time_in_status_at(&self, status: &TaskStatus, now: u64) -> u64 {
+        let target = std::mem::discriminant(status);
+        self.status_history.iter()
+            .enumerate()
+            .filter(|(_, (_, s))| std::mem::discriminant(s) == target)
+            .map(|(i, (start, _))| {
+                let end = self.status_history.get(i + 1).map_or(now, |(at, _)| *at);
+                end.saturating_sub(*start)
+            })
+            .sum()
+    }
 }
 
 #[cfg(test)]
@@ -102,11 +269,61 @@ mod tests {
         assert_eq!(task.dependencies[0], "task-1");
     }
 
+    #[test]
+    fn test_task_with_tags() {
+        let task = Task::new("task-3", "Spike caching", Stage::Design, "backend", "architect")
+            .with_tags(vec!["spike".to_string(), "urgent".to_string()]);
+        assert!(task.has_tag("spike"));
+        assert!(!task.has_tag("frontend"));
+    }
+
+    #[test]
+    fn test_time_in_status() {
+        let mut task = Task::new("task-1", "Build", Stage::Implement, "backend", "developer");
+        task.status_history = vec![
+            (100, TaskStatus::Pending),
+            (110, TaskStatus::InProgress),
+            (130, TaskStatus::Blocked("Waiting for API".into())),
+            (190, TaskStatus::InProgress),
+            (200, TaskStatus::Blocked("Flaky CI".into())),
+        ];
+
+        assert_eq!(task.time_in_status_at(&TaskStatus::Pending, 250), 10);
+        assert_eq!(task.time_in_status_at(&TaskStatus::InProgress, 250), 30);
+        assert_eq!(task.time_in_status_at(&TaskStatus::Blocked("any".into()), 250), 110);
+        assert_eq!(task.time_in_status_at(&TaskStatus::Done, 250), 0);
+    }
+
+    #[test]
+    fn test_task_deserializes_without_tags() {
+        let json = r#"{"id":"t1","name":"Old","stage":"implement","zone":"backend","status":"pending",
+            "persona":"developer","dependencies":[],"created_at":1,"updated_at":1}"#;
+        let task: Task = serde_json::from_str(json).unwrap();
+        assert!(task.tags.is_empty());
+        assert_eq!(task.priority, 0);
+    }
+
     #[test]
     fn test_task_status_serialization() {
-        let status = TaskStatus::Blocked("Waiting for API".to_string());
+        let status = TaskStatus::Blocked("Waiting for API".into());
         let json = serde_json::to_string(&status).unwrap();
         assert!(json.contains("blocked"));
         assert!(json.contains("Waiting for API"));
     }
+
+    #[test]
+    fn test_block_reason_serialization() {
+        let status = TaskStatus::Blocked(BlockReason::new(BlockCategory::NeedsHuman, "Pick a vendor"));
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(json, r#"{"blocked":{"category":"needs_human","detail":"Pick a vendor"}}"#);
+
+        let parsed: TaskStatus = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, status);
+    }
+
+    #[test]
+    fn test_block_reason_legacy_string() {
+        let parsed: TaskStatus = serde_json::from_str(r#"{"blocked":"Waiting for API"}"#).unwrap();
+        assert_eq!(parsed, TaskStatus::Blocked(BlockReason::other("Waiting for API")));
+    }
 }
@@ -254,9 +254,15 @@ impl Parser {
                 }
                 "content_block_delta" => {
                     if let Some(delta) = obj.get("delta") {
-                        if let Some(text) = delta.get("text").and_then(|v| v.as_str()) {
+                        if let Some(thinking) = delta.get("thinking").and_then(|v| v.as_str()) {
                             events.push(
                                 UnifiedEvent::new("thinking")
+                                    .with_agent_id(&self.agent_id)
+                                    .with_content(thinking),
+                            );
+                        } else if let Some(text) = delta.get("text").and_then(|v| v.as_str()) {
+                            events.push(
+                                UnifiedEvent::new("assistant")
                                     .with_agent_id(&self.agent_id)
                                     .with_content(text),
                             );
@@ -312,6 +318,14 @@ impl Parser {
                     );
                 }
             }
+
+            // Content blocks keep their order and carry the turn of the
+            // message they belong to, so replays can rebuild the interleaving
+            if matches!(event_type, "assistant" | "content_block_start" | "content_block_delta") {
+                for event in &mut events {
+                    event.turn = Some(self.current_turn);
+                }
+            }
         }
 
         events
@@ -328,12 +342,21 @@ impl Parser {
                 "text" => {
                     if let Some(text) = obj.get("text").and_then(|v| v.as_str()) {
                         events.push(
-                            UnifiedEvent::new("thinking")
+                            UnifiedEvent::new("assistant")
                                 .with_agent_id(&self.agent_id)
                                 .with_content(text),
                         );
                     }
                 }
+                "thinking" => {
+                    if let Some(thinking) = obj.get("thinking").and_then(|v| v.as_str()) {
+                        events.push(
+                            UnifiedEvent::new("thinking")
+                                .with_agent_id(&self.agent_id)
+                                .with_content(thinking),
+                        );
+                    }
+                }
                 "tool_use" => {
                     if let Some(name) = obj.get("name").and_then(|v| v.as_str()) {
                         let input = obj.get("input").cloned().unwrap_or(Value::Null);
@@ -479,6 +502,35 @@ mod tests {
         assert_eq!(events[0].tool, Some("bash".to_string()));
     }
 
+    #[test]
+    fn test_claude_mixed_content_keeps_order_and_turn() {
+        let mut parser = Parser::new("test".to_string());
+        parser.parse_line(r#"{"type":"message_start","message":{}}"#);
+        parser.parse_line(r#"{"type":"message_start","message":{}}"#);
+        let events = parser.parse_line(
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Let me look."},{"type":"tool_use","name":"Read","input":{"path":"a.rs"}},{"type":"text","text":"Found it."}]}}"#,
+        );
+        let kinds: Vec<&str> = events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(kinds, vec!["assistant", "tool_call", "assistant"]);
+        assert_eq!(events[0].content.as_deref(), Some("Let me look."));
+        assert_eq!(events[2].content.as_deref(), Some("Found it."));
+        assert!(events.iter().all(|e| e.turn == Some(2)));
+
+        let events = parser.parse_line(r#"{"type":"content_block_delta","delta":{"type":"text_delta","text":"More"}}"#);
+        assert_eq!(events[0].turn, Some(2));
+    }
+
+    #[test]
+    fn test_claude_text_vs_thinking() {
+        let mut parser = Parser::new("test".to_string());
+        let events = parser.parse_line(
+            r#"{"type":"assistant","message":{"content":[{"type":"thinking","thinking":"Check the schema"},{"type":"text","text":"Done."}]}}"#,
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "thinking");
+        assert_eq!(events[1].event_type, "assistant");
+    }
+
     #[test]
     fn test_parse_text_turn() {
         let mut parser = Parser::new("test".to_string());
@@ -357,8 +357,9 @@ export function useWebSocket() {
         // Could be added to conversation if needed
         break
 
-      // Legacy output event
+      // Legacy output event; assistant is the model's visible answer
       case 'output':
+      case 'assistant':
         if (data.agent_id || data.agentId) {
           const agentId = data.agent_id || data.agentId
           if (data.content) {